@@ -0,0 +1,66 @@
+//! Times operator and method dispatch in hot loops on both engines,
+//! with the dispatch caches and number fast paths off and then on.
+//! Run with `cargo bench --bench dispatch`.
+use std::time::{Duration, Instant};
+
+use pettyscript::{
+    parser::parse,
+    vm::{run_with_engine, set_fast_dispatch, Engine},
+};
+
+const RUNS: usize = 5;
+
+const ARITHMETIC: &str = "
+fn run(n) {
+    total = 0;
+    i = 0;
+    while i < n {
+        total = total + i * 2 % 7;
+        i = i + 1;
+    }
+    return total;
+}
+run(200000);
+";
+
+const METHODS: &str = "
+fn build(n) {
+    items = [];
+    for i in range(n) {
+        items.push(i.abs());
+    }
+    return items.len();
+}
+build(100000);
+";
+
+fn best_of(source: &str, engine: Engine, fast_dispatch: bool) -> Duration {
+    set_fast_dispatch(fast_dispatch);
+    let ast = parse(source).unwrap();
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run_with_engine(&ast, engine);
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    println!(
+        "{:<12} {:<10} {:>10} {:>10} {:>8}",
+        "", "", "uncached", "cached", "speedup"
+    );
+    for (name, source) in [("arithmetic", ARITHMETIC), ("methods", METHODS)] {
+        for (engine_name, engine) in [("tree", Engine::TreeWalk), ("bytecode", Engine::Bytecode)] {
+            let uncached = best_of(source, engine, false);
+            let cached = best_of(source, engine, true);
+            let speedup = uncached.as_secs_f64() / cached.as_secs_f64();
+            println!(
+                "{name:<12} {engine_name:<10} {:>10.2?} {:>10.2?} {speedup:>7.2}x",
+                uncached, cached
+            );
+        }
+    }
+}
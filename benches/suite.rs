@@ -0,0 +1,147 @@
+//! Times parsing and executing each workload in `benches/workloads` separately.
+//!
+//! Run with `cargo bench --bench suite -- [filter] [--save <file>] [--baseline <file>]`.
+//! Results are saved as tab separated `workload parse_ns execute_ns` lines,
+//! to `target/bench/suite.tsv` unless `--save` is given,
+//! and compared against the results saved in `--baseline` when given.
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use pettyscript::{parser::parse, vm::run_virtual_machine};
+
+/// Each workload is timed this many times after a warm up run, keeping the fastest.
+const RUNS: usize = 5;
+
+struct Timing {
+    parse: Duration,
+    execute: Duration,
+}
+
+struct Options {
+    filter: Option<String>,
+    save: PathBuf,
+    baseline: Option<PathBuf>,
+}
+
+impl Options {
+    fn from_args() -> Self {
+        let mut options = Self {
+            filter: None,
+            save: Path::new(env!("CARGO_MANIFEST_DIR")).join("target/bench/suite.tsv"),
+            baseline: None,
+        };
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--save" => options.save = args.next().expect("Expected a file to save to").into(),
+                "--baseline" => {
+                    options.baseline = Some(args.next().expect("Expected a baseline file").into());
+                }
+                // Passed by `cargo bench`.
+                "--bench" => {}
+                filter => options.filter = Some(filter.into()),
+            }
+        }
+        options
+    }
+}
+
+fn best_of(mut run: impl FnMut()) -> Duration {
+    run();
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn time(source: &str) -> Timing {
+    let parse_time = best_of(|| {
+        parse(source).unwrap();
+    });
+    let ast = parse(source).unwrap();
+    let execute = best_of(|| {
+        run_virtual_machine(&ast);
+    });
+    Timing {
+        parse: parse_time,
+        execute,
+    }
+}
+
+fn workloads(filter: Option<&str>) -> Vec<(String, String)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/workloads");
+    let mut workloads: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "pty"))
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            (name, fs::read_to_string(&path).unwrap())
+        })
+        .filter(|(name, _)| filter.is_none_or(|filter| name.contains(filter)))
+        .collect();
+    workloads.sort();
+    workloads
+}
+
+fn read_results(path: &Path) -> HashMap<String, Timing> {
+    let results = fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("Couldn't read {}: {error}", path.display()));
+    results
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.to_string();
+            let mut nanos = || Some(Duration::from_nanos(fields.next()?.parse().ok()?));
+            let timing = Timing {
+                parse: nanos()?,
+                execute: nanos()?,
+            };
+            Some((name, timing))
+        })
+        .collect()
+}
+
+fn change(before: Duration, after: Duration) -> String {
+    let change = (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+    format!("{change:+.1}%")
+}
+
+fn main() {
+    let options = Options::from_args();
+    let baseline = options.baseline.as_deref().map(read_results);
+    let mut saved = String::new();
+    for (name, source) in workloads(options.filter.as_deref()) {
+        let timing = time(&source);
+        let mut line = format!(
+            "{name:<16} parse {:>10.2?}   execute {:>10.2?}",
+            timing.parse, timing.execute
+        );
+        if let Some(before) = baseline.as_ref().and_then(|baseline| baseline.get(&name)) {
+            line += &format!(
+                "   ({} / {})",
+                change(before.parse, timing.parse),
+                change(before.execute, timing.execute)
+            );
+        }
+        println!("{line}");
+        saved += &format!(
+            "{name}\t{}\t{}\n",
+            timing.parse.as_nanos(),
+            timing.execute.as_nanos()
+        );
+    }
+    if let Some(dir) = options.save.parent() {
+        fs::create_dir_all(dir).unwrap();
+    }
+    fs::write(&options.save, saved).unwrap();
+    println!("Saved results to {}", options.save.display());
+}
@@ -14,7 +14,11 @@ pub enum Node {
     WhileLoop(Arc<Node>, Arc<[Node]>),
     ForLoop(Arc<str>, Arc<Node>, Arc<[Node]>),
     ReturnState(Arc<Node>),
+    Yield(Arc<Node>),
+    Try(Arc<Node>),
     BreakState,
+    /// Declares names assigned in this function as belonging to an enclosing one.
+    Nonlocal(Arc<[Arc<str>]>),
     FuncDef(Arc<str>, Arc<[Arc<str>]>, Arc<[Node]>),
     ClassDef(Arc<str>, Arc<[Arc<str>]>, Arc<[Node]>),
     Empty,
@@ -116,6 +120,7 @@ impl fmt::Debug for Node {
                 .field("inner", node)
                 .finish(),
             Self::BreakState => write!(f, "break"),
+            Self::Nonlocal(names) => f.debug_tuple("nonlocal").field(names).finish(),
             Self::Empty => write!(f, "empty"),
             Self::ForLoop(ident, expr, block) => f
                 .debug_struct("for_loop")
@@ -146,6 +151,8 @@ impl fmt::Debug for Node {
                 .finish(),
             Self::Literal(literal) => write!(f, "{literal:?}"),
             Self::ReturnState(expr) => f.debug_tuple("return").field(expr).finish(),
+            Self::Yield(expr) => f.debug_tuple("yield").field(expr).finish(),
+            Self::Try(expr) => f.debug_tuple("try").field(expr).finish(),
             Self::SetEq(ident, expr) => f
                 .debug_struct("set_eq")
                 .field("left", ident)
@@ -217,9 +224,76 @@ impl Node {
     pub fn for_loop(target: &str, iter: Node, block: Vec<Node>) -> Self {
         Self::ForLoop(target.into(), iter.into(), block.into())
     }
+    pub fn nonlocal(names: Vec<&str>) -> Self {
+        Self::Nonlocal(vec_box_str(names))
+    }
     pub fn closure(args: Vec<&str>, body: &[Node]) -> Self {
         Self::Closure(vec_box_str(args), body.into())
     }
+    pub fn try_expr(value: Node) -> Self {
+        Self::Try(Arc::new(value))
+    }
+    pub fn yield_expr(value: Node) -> Self {
+        Self::Yield(Arc::new(value))
+    }
+    /// Whether a function body yields, making calls to it return a generator.
+    /// Nested functions, closures and classes are generators of their own so they aren't searched.
+    pub fn contains_yield(nodes: &[Node]) -> bool {
+        nodes.iter().any(Node::yields)
+    }
+    /// The names a function body declares `nonlocal`, nested functions have their own.
+    pub fn nonlocals(nodes: &[Node]) -> Vec<Arc<str>> {
+        let mut names = Vec::new();
+        Self::collect_nonlocals(nodes, &mut names);
+        names
+    }
+    fn collect_nonlocals(nodes: &[Node], names: &mut Vec<Arc<str>>) {
+        for node in nodes {
+            match node {
+                Self::Nonlocal(declared) => names.extend(declared.iter().cloned()),
+                Self::Block(nodes)
+                | Self::Globals(nodes)
+                | Self::WhileLoop(_, nodes)
+                | Self::ForLoop(_, _, nodes) => Self::collect_nonlocals(nodes, names),
+                Self::IfState(_, block, or_else) => {
+                    Self::collect_nonlocals(block, names);
+                    if let Some(or_else) = or_else {
+                        Self::collect_nonlocals(std::slice::from_ref(or_else), names);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    fn yields(&self) -> bool {
+        match self {
+            Self::Yield(_) => true,
+            Self::Closure(..) | Self::FuncDef(..) | Self::ClassDef(..) => false,
+            Self::Literal(Literal::List(nodes)) | Self::Block(nodes) | Self::Globals(nodes) => {
+                Self::contains_yield(nodes)
+            }
+            Self::Literal(_)
+            | Self::Ident(_)
+            | Self::BreakState
+            | Self::Nonlocal(_)
+            | Self::Empty => false,
+            Self::BinExpr(_, nodes) => nodes.0.yields() || nodes.1.yields(),
+            Self::UnaryOp(_, node)
+            | Self::ReturnState(node)
+            | Self::SetEq(_, node)
+            | Self::Try(node) => node.yields(),
+            Self::GetItemIndex(_, node) => node.yields(),
+            Self::FuncCall(_, args) => Self::contains_yield(args),
+            Self::IfState(condition, block, or_else) => {
+                condition.yields()
+                    || Self::contains_yield(block)
+                    || or_else.as_ref().is_some_and(|node| node.yields())
+            }
+            Self::WhileLoop(condition, block) => condition.yields() || Self::contains_yield(block),
+            Self::ForLoop(_, iter, block) => iter.yields() || Self::contains_yield(block),
+            Self::SetItemIndex(_, index, expr) => index.yields() || expr.yields(),
+        }
+    }
 }
 fn vec_box_str(input: Vec<&str>) -> Arc<[Arc<str>]> {
     input
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, time::Duration};
 use thiserror::Error;
 
 #[derive(Debug, Clone, Copy, Error, PartialEq)]
@@ -7,9 +7,13 @@ pub enum PettyParseError {
     Expr,
     Ident,
     Literal,
+    Int,
+    IntOverflow,
     Float,
-    FloatDigit,
     TermExpr,
+    FString,
+    String,
+    Escape,
 }
 
 impl fmt::Display for PettyParseError {
@@ -17,3 +21,25 @@ impl fmt::Display for PettyParseError {
         write!(f, "{self:?}")
     }
 }
+
+/// An error aborting the running program, raised by unwinding with it as the payload
+/// so it gets through any script code between the check and the host.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum RuntimeError {
+    #[error("RecursionError: maximum recursion depth of {limit} exceeded")]
+    Recursion { limit: usize },
+    #[error("StepLimitError: ran out of its {limit} steps")]
+    Steps { limit: u64 },
+    #[error("TimeoutError: still running after {limit:?}")]
+    Timeout { limit: Duration },
+    #[error("CancelledError: stopped by the host")]
+    Cancelled,
+    #[error("MemoryError: strings and containers would take more than {limit} bytes")]
+    Memory { limit: usize },
+}
+
+impl RuntimeError {
+    pub fn raise(self) -> ! {
+        std::panic::resume_unwind(Box::new(self))
+    }
+}
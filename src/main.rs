@@ -1,8 +1,22 @@
-use pettyscript::read_ast;
+use pettyscript::{
+    optimizer, read_ast,
+    vm::{Engine, Limits},
+};
 
 fn main() -> Result<(), ()> {
-    let input = std::fs::read_to_string("example.pty").unwrap();
-    let ast = read_ast(&input)?;
-    pettyscript::vm::run_virtual_machine(&ast);
+    let mut args = std::env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| "example.pty".into());
+    let input = std::fs::read_to_string(&path).unwrap();
+    pettyscript::vm::set_args(std::iter::once(path).chain(args).collect());
+    let mut ast = read_ast(&input)?;
+    if optimizer::from_env() {
+        ast = optimizer::optimize(&ast);
+    }
+    if let Err(error) =
+        pettyscript::vm::run_with_limits(&ast, Engine::from_env(), Limits::from_env())
+    {
+        eprintln!("{error}");
+        return Err(());
+    }
     Ok(())
 }
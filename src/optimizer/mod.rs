@@ -0,0 +1,192 @@
+//! An optional pass over the [`Node`]s of a program before running it on either engine.
+//! Folds operators on literals, removes branches that can never run and flattens nested blocks.
+//! Only folds what the builtins compute the same way, anything else is left for runtime.
+mod tests;
+
+use std::sync::Arc;
+
+use crate::ast::{BinOp, Literal, Node, UnaryOp};
+
+/// Reads whether to optimize from the `PETTY_OPTIMIZE` environment variable, `on` or `off`.
+/// Defaults to on.
+pub fn from_env() -> bool {
+    match std::env::var("PETTY_OPTIMIZE").as_deref() {
+        Err(_) | Ok("on") => true,
+        Ok("off") => false,
+        Ok(other) => panic!("Unknown PETTY_OPTIMIZE value {other:?}, expected `on` or `off`"),
+    }
+}
+
+/// Optimizes a program, each top level statement still gives a single value.
+pub fn optimize(program: &Node) -> Node {
+    match program {
+        Node::Globals(nodes) => Node::Globals(nodes.iter().map(fold).collect()),
+        Node::Block(nodes) => Node::Block(nodes.iter().map(fold).collect()),
+        node => fold(node),
+    }
+}
+
+/// Optimizes a list of statements, splicing in the nested blocks.
+fn block(nodes: &[Node]) -> Arc<[Node]> {
+    let mut optimized = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match fold(node) {
+            Node::Block(nodes) => optimized.extend(nodes.iter().cloned()),
+            node => optimized.push(node),
+        }
+    }
+    optimized.into()
+}
+
+fn fold(node: &Node) -> Node {
+    match node {
+        Node::BinExpr(BinOp::GetItem, nodes) => {
+            Node::bin_expr(BinOp::GetItem, fold(&nodes.0), fold(&nodes.1))
+        }
+        Node::BinExpr(op, nodes) => {
+            let lhs = fold(&nodes.0);
+            let rhs = fold(&nodes.1);
+            match (constant(&lhs), constant(&rhs)) {
+                (Some(lhs), Some(rhs)) => binary(*op, lhs, rhs),
+                _ => None,
+            }
+            .unwrap_or_else(|| Node::bin_expr(*op, lhs, rhs))
+        }
+        Node::UnaryOp(op, inner) => {
+            let inner = fold(inner);
+            constant(&inner)
+                .and_then(|constant| unary(*op, constant))
+                .unwrap_or_else(|| Node::unary_expr(*op, inner))
+        }
+        Node::Literal(Literal::List(items)) => {
+            Node::Literal(Literal::List(items.iter().map(fold).collect()))
+        }
+        Node::FuncCall(name, args) => Node::FuncCall(name.clone(), args.iter().map(fold).collect()),
+        Node::Closure(params, body) => Node::Closure(params.clone(), block(body)),
+        Node::FuncDef(name, params, body) => {
+            Node::FuncDef(name.clone(), params.clone(), block(body))
+        }
+        Node::ClassDef(name, fields, methods) => Node::ClassDef(
+            name.clone(),
+            fields.clone(),
+            methods.iter().map(fold).collect(),
+        ),
+        Node::IfState(condition, body, or_else) => {
+            if_state(condition, body, or_else.as_ref().map(Arc::as_ref))
+        }
+        Node::WhileLoop(condition, body) => {
+            let condition = fold(condition);
+            let body = block(body);
+            if condition == Node::Literal(Literal::Bool(false)) && !declares(&body) {
+                return Node::Block(Arc::new([]));
+            }
+            Node::WhileLoop(Arc::new(condition), body)
+        }
+        Node::ForLoop(target, iter, body) => {
+            Node::ForLoop(target.clone(), Arc::new(fold(iter)), block(body))
+        }
+        Node::Block(nodes) => Node::Block(block(nodes)),
+        Node::Globals(nodes) => Node::Globals(block(nodes)),
+        Node::ReturnState(expr) => Node::ReturnState(Arc::new(fold(expr))),
+        Node::Yield(expr) => Node::yield_expr(fold(expr)),
+        Node::Try(expr) => Node::try_expr(fold(expr)),
+        Node::SetEq(name, expr) => Node::SetEq(name.clone(), Arc::new(fold(expr))),
+        Node::GetItemIndex(name, index) => Node::GetItemIndex(name.clone(), Arc::new(fold(index))),
+        Node::SetItemIndex(name, index, expr) => {
+            Node::SetItemIndex(name.clone(), Arc::new(fold(index)), Arc::new(fold(expr)))
+        }
+        Node::Literal(_) | Node::Ident(_) | Node::BreakState | Node::Nonlocal(_) | Node::Empty => {
+            node.clone()
+        }
+    }
+}
+
+/// Replaces an `if` on a literal bool with the branch it takes.
+fn if_state(condition: &Node, body: &[Node], or_else: Option<&Node>) -> Node {
+    let condition = fold(condition);
+    let body = block(body);
+    let or_else = or_else.map(fold);
+    if let Node::Literal(Literal::Bool(taken)) = condition {
+        let (taken, dropped) = if taken {
+            (Node::Block(body.clone()), or_else.clone())
+        } else {
+            (
+                or_else.clone().unwrap_or(Node::Block(Arc::new([]))),
+                Some(Node::Block(body.clone())),
+            )
+        };
+        if dropped.is_none_or(|dropped| !declares(std::slice::from_ref(&dropped))) {
+            return taken;
+        }
+    }
+    Node::IfState(Arc::new(condition), body, or_else.map(Arc::new))
+}
+
+/// Whether removing `nodes` would change the function around them,
+/// by making it stop being a generator or forgetting a `nonlocal` declaration.
+fn declares(nodes: &[Node]) -> bool {
+    Node::contains_yield(nodes) || !Node::nonlocals(nodes).is_empty()
+}
+
+enum Constant<'a> {
+    Num(f64),
+    Bool(bool),
+    Str(&'a Arc<str>),
+}
+
+fn constant(node: &Node) -> Option<Constant<'_>> {
+    match node {
+        #[allow(clippy::cast_precision_loss)]
+        Node::Literal(Literal::Int(int)) => Some(Constant::Num(*int as f64)),
+        Node::Literal(Literal::Float(float)) => Some(Constant::Num(*float)),
+        Node::Literal(Literal::Bool(bool)) => Some(Constant::Bool(*bool)),
+        Node::Literal(Literal::String(string)) => Some(Constant::Str(string)),
+        _ => None,
+    }
+}
+
+/// Computes `lhs op rhs` like the `PtyNum`, `PtyBool` and `PtyStr` methods would.
+#[allow(clippy::float_cmp)]
+fn binary(op: BinOp, lhs: Constant, rhs: Constant) -> Option<Node> {
+    let literal = match (lhs, rhs) {
+        (Constant::Num(lhs), Constant::Num(rhs)) => match op {
+            BinOp::Add => Literal::Float(lhs + rhs),
+            BinOp::Sub => Literal::Float(lhs - rhs),
+            BinOp::Mul => Literal::Float(lhs * rhs),
+            BinOp::Div => Literal::Float(lhs / rhs),
+            BinOp::Mod => Literal::Float(lhs % rhs),
+            BinOp::IsEq => Literal::Bool(lhs == rhs),
+            BinOp::LT => Literal::Bool(lhs < rhs),
+            BinOp::GT => Literal::Bool(lhs > rhs),
+            BinOp::LTEq => Literal::Bool(lhs <= rhs),
+            BinOp::GTEq => Literal::Bool(lhs >= rhs),
+            _ => return None,
+        },
+        (Constant::Bool(lhs), Constant::Bool(rhs)) => match op {
+            BinOp::And => Literal::Bool(lhs && rhs),
+            BinOp::Or => Literal::Bool(lhs || rhs),
+            BinOp::IsEq => Literal::Bool(lhs == rhs),
+            _ => return None,
+        },
+        (Constant::Str(lhs), Constant::Str(rhs)) => match op {
+            BinOp::Add => Literal::String(format!("{lhs}{rhs}").into()),
+            BinOp::IsEq => Literal::Bool(lhs == rhs),
+            BinOp::LT => Literal::Bool(lhs < rhs),
+            BinOp::GT => Literal::Bool(lhs > rhs),
+            BinOp::LTEq => Literal::Bool(lhs <= rhs),
+            BinOp::GTEq => Literal::Bool(lhs >= rhs),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(Node::Literal(literal))
+}
+
+fn unary(op: UnaryOp, inner: Constant) -> Option<Node> {
+    let literal = match (op, inner) {
+        (UnaryOp::Neg, Constant::Num(num)) => Literal::Float(-num),
+        (UnaryOp::Not, Constant::Bool(bool)) => Literal::Bool(!bool),
+        _ => return None,
+    };
+    Some(Node::Literal(literal))
+}
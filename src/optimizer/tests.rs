@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod optimizer_tests {
+    use super::super::optimize;
+    use crate::{
+        ast::{BinOp, Literal, Node},
+        parser::parse,
+    };
+
+    fn assert_optimized(source: &str, expected: Vec<Node>) {
+        let output = optimize(&parse(source).unwrap());
+        assert_eq!(output, Node::Globals(expected.into()));
+    }
+    #[test]
+    fn folds_arithmetic() {
+        assert_optimized(
+            "seconds = 60 * 60 * 24; -(2 - 5);",
+            vec![
+                Node::set_eq("seconds", Node::literal(86400.0)),
+                Node::literal(3.0),
+            ],
+        );
+    }
+    #[test]
+    fn folds_comparisons_and_strings() {
+        assert_optimized(
+            "1 + 1 == 2 && !false; 'pet' + 'ty'; 'a' < 'b';",
+            vec![
+                Node::literal(true),
+                Node::literal("petty"),
+                Node::literal(true),
+            ],
+        );
+    }
+    #[test]
+    fn keeps_dynamic_operands() {
+        let source = "x * (2 * 3); 'a' * 3; 1 + 'a';";
+        assert_optimized(
+            source,
+            vec![
+                Node::bin_expr(BinOp::Mul, Node::ident("x"), Node::literal(6.0)),
+                Node::literal_expr(BinOp::Mul, "a", 3),
+                Node::literal_expr(BinOp::Add, 1, "a"),
+            ],
+        );
+    }
+    #[test]
+    fn removes_dead_branches() {
+        assert_optimized(
+            "if false { a(); } elif 1 > 2 { b(); } else { c(); }
+            if true { d(); }
+            while false { e(); }",
+            vec![
+                Node::block(vec![Node::func_call("c", vec![])]),
+                Node::block(vec![Node::func_call("d", vec![])]),
+                Node::block(vec![]),
+            ],
+        );
+    }
+    #[test]
+    fn flattens_nested_blocks() {
+        assert_optimized(
+            "fn f() { { a(); { b(); } } if true { c(); } if false { d(); } }",
+            vec![Node::func_def(
+                "f",
+                vec![],
+                vec![
+                    Node::func_call("a", vec![]),
+                    Node::func_call("b", vec![]),
+                    Node::func_call("c", vec![]),
+                ],
+            )],
+        );
+    }
+    #[test]
+    fn keeps_dead_yields_and_nonlocals() {
+        let source = "fn gen() { if false { yield 1; } }
+            fn inner() { while false { nonlocal x; } }";
+        assert_eq!(optimize(&parse(source).unwrap()), parse(source).unwrap());
+    }
+    #[test]
+    fn folds_inside_lists_and_calls() {
+        assert_optimized(
+            "print([1 + 2, 'x' + 'y']);",
+            vec![Node::func_call(
+                "print",
+                vec![Node::literal(Literal::List(
+                    vec![Node::literal(3.0), Node::literal("xy")].into(),
+                ))],
+            )],
+        );
+    }
+}
@@ -1,51 +1,64 @@
 #[allow(clippy::wildcard_imports)]
 use super::*;
-pub fn bin_expr(input: &str) -> IRes {
+pub fn bin_expr(input: &str) -> IRes<'_> {
     err(condition, ParseErr::Expr)(input)
 }
-fn paren_bin_expr(i: &str) -> IRes {
+fn paren_bin_expr(i: &str) -> IRes<'_> {
     delimited(spar('('), bin_expr, spar(')'))(i)
 }
-fn condition(input: &str) -> IRes {
+fn condition(input: &str) -> IRes<'_> {
     let (input, initial) = comparison(input)?;
     let (input, remainder) = many0(pair(binop_cond, comparison))(input)?;
     Ok((input, fold_exprs(initial, remainder)))
 }
-fn comparison(input: &str) -> IRes {
+fn comparison(input: &str) -> IRes<'_> {
     let (input, initial) = lower(input)?;
     let (input, remainder) = many0(pair(binop_comp, lower))(input)?;
     Ok((input, fold_exprs(initial, remainder)))
 }
-fn lower(input: &str) -> IRes {
+fn lower(input: &str) -> IRes<'_> {
     let (input, initial) = upper(input)?;
     let (input, remainder) = many0(pair(binop_lower, upper))(input)?;
     Ok((input, fold_exprs(initial, remainder)))
 }
-fn upper(input: &str) -> IRes {
+fn upper(input: &str) -> IRes<'_> {
     let (input, initial) = get_item(input)?;
     let (input, remainder) = many0(pair(binop_upper, get_item))(input)?;
     Ok((input, fold_exprs(initial, remainder)))
 }
-fn get_item(input: &str) -> IRes {
+enum Postfix {
+    GetItem(Node),
+    Try,
+}
+/// `.` lookups and postfix `?`, applied left to right so `a.b()?.c` works.
+fn get_item(input: &str) -> IRes<'_> {
     let (input, initial) = factor(input)?;
-    let (input, remainder) =
-        many0(pair(map(spar('.'), |_| BinOp::GetItem), get_item_suffix))(input)?;
-    Ok((input, fold_exprs(initial, remainder)))
+    let (input, postfixes) = many0(alt((
+        preceded(spar('.'), get_item_suffix).map(Postfix::GetItem),
+        map(spar('?'), |_| Postfix::Try),
+    )))(input)?;
+    let node = postfixes
+        .into_iter()
+        .fold(initial, |acc, postfix| match postfix {
+            Postfix::GetItem(item) => Node::BinExpr(BinOp::GetItem, Arc::new((acc, item))),
+            Postfix::Try => Node::Try(Arc::new(acc)),
+        });
+    Ok((input, node))
 }
-fn get_item_suffix(input: &str) -> IRes {
+fn get_item_suffix(input: &str) -> IRes<'_> {
     alt((function_call, map(sp(ident), Node::Ident)))(input)
 }
-fn factor(input: &str) -> IRes {
+fn factor(input: &str) -> IRes<'_> {
     alt((paren_bin_expr, node_value, unary_expr))(input)
 }
 
-fn binop_lower(input: &str) -> IRes<BinOp> {
+fn binop_lower(input: &str) -> IRes<'_, BinOp> {
     sp(alt((
         map(char('+'), |_| BinOp::Add),
         map(char('-'), |_| BinOp::Sub),
     )))(input)
 }
-fn binop_upper(input: &str) -> IRes<BinOp> {
+fn binop_upper(input: &str) -> IRes<'_, BinOp> {
     sp(alt((
         map(char('*'), |_| BinOp::Mul),
         map(char('/'), |_| BinOp::Div),
@@ -53,13 +66,13 @@ fn binop_upper(input: &str) -> IRes<BinOp> {
     )))(input)
 }
 
-fn binop_cond(input: &str) -> IRes<BinOp> {
+fn binop_cond(input: &str) -> IRes<'_, BinOp> {
     sp(alt((
         map(tag("&&"), |_| BinOp::And),
         map(tag("||"), |_| BinOp::Or),
     )))(input)
 }
-fn binop_comp(input: &str) -> IRes<BinOp> {
+fn binop_comp(input: &str) -> IRes<'_, BinOp> {
     sp(alt((
         map(tag("<="), |_| BinOp::LTEq),
         map(tag(">="), |_| BinOp::GTEq),
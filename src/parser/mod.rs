@@ -1,11 +1,15 @@
 mod bin_expr;
+mod number;
 mod statements;
+mod string;
 mod tests;
 
 use std::sync::Arc;
 
 use bin_expr::bin_expr;
+use number::{float, int};
 use statements::statement;
+use string::{fstring, string};
 
 use crate::{
     ast::{BinOp, Literal, Node, UnaryOp},
@@ -14,9 +18,9 @@ use crate::{
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_while},
-    character::complete::{char, digit1, one_of},
+    character::complete::{char, one_of},
     combinator::{cut, map, opt, recognize, value},
-    multi::{many0, separated_list0},
+    multi::{many0, separated_list0, separated_list1},
     sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult, Parser,
 };
@@ -33,41 +37,41 @@ use crate::NomErr;
 use self::statements::keyword_name;
 type ParseErr = PettyParseError;
 
-pub fn parse(input: &str) -> Result<Node, NomErr> {
+pub fn parse(input: &str) -> Result<Node, NomErr<'_>> {
     final_parser(map(nodes, Node::Globals))(input)
 }
-fn nodes(input: &str) -> IRes<Arc<[Node]>> {
+fn nodes(input: &str) -> IRes<'_, Arc<[Node]>> {
     map(many0(node), Arc::from)(input)
 }
 #[inline]
-fn node(input: &str) -> IRes {
+fn node(input: &str) -> IRes<'_> {
     let (rem, output) = sp(err(
         alt((statement, map(block, Node::Block), terminated_expr)),
         PettyParseError::Node,
     ))(input)?;
     Ok((eat_comments(rem), output))
 }
-fn terminated_expr(input: &str) -> IRes {
+fn terminated_expr(input: &str) -> IRes<'_> {
     err(
         alt((set_equals, set_item_index, bin_expr)).terminated(cut(spar(';'))),
         ParseErr::TermExpr,
     )(input)
 }
-fn closure(input: &str) -> IRes {
+fn closure(input: &str) -> IRes<'_> {
     map(
         pair(delimited(spar('|'), params, spar('|')), block),
         |(args, body)| Node::Closure(args, body),
     )(input)
 }
 
-fn set_equals(input: &str) -> IRes {
+fn set_equals(input: &str) -> IRes<'_> {
     map(
         separated_pair(type_hinted, spar('='), node_expr),
         |(ident, expr)| Node::SetEq(ident, Arc::new(expr)),
     )(input)
 }
 
-fn set_item_index(input: &str) -> IRes {
+fn set_item_index(input: &str) -> IRes<'_> {
     map(
         separated_pair(
             pair(sp(ident), delimited(spar('['), bin_expr, spar(']'))),
@@ -79,28 +83,35 @@ fn set_item_index(input: &str) -> IRes {
 }
 
 #[inline]
-fn node_expr(input: &str) -> IRes {
+fn node_expr(input: &str) -> IRes<'_> {
     bin_expr(input)
 }
-fn node_value(input: &str) -> IRes {
-    alt((unary_expr, node_value_raw, closure))(input)
+fn node_value(input: &str) -> IRes<'_> {
+    alt((unary_expr, yield_expr, node_value_raw, closure))(input)
 }
-fn node_value_raw(input: &str) -> IRes {
+/// `yield value`, evaluating to whatever the caller passes to `send`.
+fn yield_expr(input: &str) -> IRes<'_> {
+    preceded(keyword_name("yield"), opt(node_expr))
+        .map(|node| Node::Yield(Arc::new(node.unwrap_or(Node::Literal(Literal::Null)))))
+        .parse(input)
+}
+fn node_value_raw(input: &str) -> IRes<'_> {
     alt((
+        sp(fstring),
         literal.map(Node::Literal),
         function_call,
         get_item_index,
         sp(ident).map(Node::Ident),
     ))(input)
 }
-fn get_item_index(input: &str) -> IRes {
+fn get_item_index(input: &str) -> IRes<'_> {
     map(
         pair(sp(ident), delimited(spar('['), bin_expr, spar(']'))),
         |(ident, expr)| Node::GetItemIndex(ident, expr.into()),
     )(input)
 }
 
-fn unary_expr(input: &str) -> IRes {
+fn unary_expr(input: &str) -> IRes<'_> {
     let unary_op = sp(alt((
         map(char('!'), |_| UnaryOp::Not),
         map(char('+'), |_| UnaryOp::Plus),
@@ -111,11 +122,11 @@ fn unary_expr(input: &str) -> IRes {
         |(op, node)| Node::UnaryOp(op, Arc::new(node)),
     )(input)
 }
-fn params(input: &str) -> IRes<Arc<[Arc<str>]>> {
+fn params(input: &str) -> IRes<'_, Arc<[Arc<str>]>> {
     let (rem, nodes) = terminated(separated_list0(spar(','), type_hinted), opt(spar(',')))(input)?;
     Ok((rem, nodes.into()))
 }
-fn function_call(i: &str) -> IRes {
+fn function_call(i: &str) -> IRes<'_> {
     pair(
         sp(ident),
         delimited(spar('('), function_args, cut(spar(')'))),
@@ -123,11 +134,11 @@ fn function_call(i: &str) -> IRes {
     .map(|(name, args)| Node::FuncCall(name, args))
     .parse(i)
 }
-fn function_args(i: &str) -> IRes<Arc<[Node]>> {
+fn function_args(i: &str) -> IRes<'_, Arc<[Node]>> {
     let (rem, nodes) = sp(separated_list0(spar(','), sp(node_expr)))(i)?;
     Ok((rem, nodes.into()))
 }
-fn block(i: &str) -> IRes<Arc<[Node]>> {
+fn block(i: &str) -> IRes<'_, Arc<[Node]>> {
     delimited(spar('{'), nodes, spar('}'))(i)
 }
 fn fold_exprs(initial: Node, remainder: Vec<(BinOp, Node)>) -> Node {
@@ -150,13 +161,13 @@ fn eat_comments(mut input: &str) -> &str {
         input = input.trim();
     }
 }
-fn type_hinted(input: &str) -> IRes<Arc<str>> {
+fn type_hinted(input: &str) -> IRes<'_, Arc<str>> {
     alt((
         terminated(sp(ident), opt(pair(spar(':'), sp(ident)))),
         sp(ident),
     ))(input)
 }
-fn ident(i: &str) -> IRes<Arc<str>> {
+fn ident(i: &str) -> IRes<'_, Arc<str>> {
     err(
         recognize(tuple((alt((alpha, char('_'))), take_while(is_ident_char)))),
         ParseErr::Ident,
@@ -164,7 +175,7 @@ fn ident(i: &str) -> IRes<Arc<str>> {
     .map(Arc::from)
     .parse(i)
 }
-fn literal(i: &str) -> IRes<Literal> {
+fn literal(i: &str) -> IRes<'_, Literal> {
     sp(err(
         alt((
             map(boolean, Literal::Bool),
@@ -181,7 +192,7 @@ fn literal(i: &str) -> IRes<Literal> {
 fn is_ident_char(c: char) -> bool {
     matches!(c, 'a'..='z'|'A'..='Z'|'0'..='9'|'_')
 }
-fn list(i: &str) -> IRes<Vec<Node>> {
+fn list(i: &str) -> IRes<'_, Vec<Node>> {
     delimited(
         char('['),
         separated_list0(spar(','), node_expr).terminated(opt(spar(','))),
@@ -189,37 +200,10 @@ fn list(i: &str) -> IRes<Vec<Node>> {
     )(i)
 }
 #[inline]
-fn int(i: &str) -> IRes<i128> {
-    map(digit1, |s: &str| s.parse().unwrap())(i)
-}
-fn float(i: &str) -> IRes<f64> {
-    let mut parser = recognize(err(
-        tuple((
-            sp(opt(one_of("+-"))),
-            sp(digit1),
-            char('.'),
-            err(digit1, ParseErr::FloatDigit),
-        )),
-        ParseErr::Float,
-    ));
-    let (rem, consumed) = match parser(i) {
-        Ok(output) => output,
-        Err(err) => return Err(err),
-    };
-    let float = consumed
-        .parse()
-        .unwrap_or_else(|e| panic!("{e} : {consumed:?}"));
-    Ok((rem, float))
-}
-#[inline]
-fn string(i: &str) -> IRes<&str> {
-    delimited(char('"'), take_while(|c| c != '"'), cut(char('"')))(i)
-}
-#[inline]
-fn boolean(input: &str) -> IRes<bool> {
+fn boolean(input: &str) -> IRes<'_, bool> {
     alt((value(true, tag("true")), value(false, tag("false"))))(input)
 }
-fn alpha(i: &str) -> IRes<char> {
+fn alpha(i: &str) -> IRes<'_, char> {
     one_of("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ")(i)
 }
 fn sp<'a, O, E, P: Parser<&'a str, O, E>>(
@@ -238,11 +222,10 @@ fn err<'a, O, P: Parser<&'a str, O, NomErr<'a>>>(
             match e {
                 nom::Err::Error(_) => nom::Err::Error(err),
                 nom::Err::Failure(e) => match &e {
-                    ErrorTree::Base { location: _, kind }
-                        if matches!(kind, BaseErrorKind::External(_err)) =>
-                    {
-                        nom::Err::Failure(e)
-                    }
+                    ErrorTree::Base {
+                        location: _,
+                        kind: BaseErrorKind::External(_),
+                    } => nom::Err::Failure(e),
                     _ => nom::Err::Failure(err),
                 },
                 nom::Err::Incomplete(_) => todo!(),
@@ -251,7 +234,7 @@ fn err<'a, O, P: Parser<&'a str, O, NomErr<'a>>>(
     }
 }
 
-fn new_error(i: &str, kind: ParseErr) -> NomErr {
+fn new_error(i: &str, kind: ParseErr) -> NomErr<'_> {
     ErrorTree::Base {
         location: i,
         kind: nom_supreme::error::BaseErrorKind::External(Box::new(kind)),
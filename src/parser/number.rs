@@ -0,0 +1,59 @@
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use nom::{character::complete::satisfy, combinator::not};
+
+/// Digits in `radix`, allowing `_` separators after the first digit.
+fn digits<'a>(radix: u32) -> impl FnMut(&'a str) -> IRes<'a, &'a str> {
+    recognize(pair(
+        satisfy(move |c: char| c.is_digit(radix)),
+        take_while(move |c: char| c.is_digit(radix) || c == '_'),
+    ))
+}
+
+/// An integer literal: decimal `1_000`, hex `0xff`, octal `0o17` or binary `0b1010`.
+/// Literals that don't fit in an `i128` are a parse error.
+pub fn int(i: &str) -> IRes<'_, i128> {
+    let (rem, radix, body) = match i.get(..2) {
+        Some(prefix @ ("0x" | "0o" | "0b")) => {
+            let radix = match prefix {
+                "0x" => 16,
+                "0o" => 8,
+                _ => 2,
+            };
+            let (rem, body) = cut(err(digits(radix), ParseErr::Int))(&i[2..])?;
+            (rem, radix, body)
+        }
+        _ => {
+            let (rem, body) = digits(10)(i)?;
+            (rem, 10, body)
+        }
+    };
+    match i128::from_str_radix(&body.replace('_', ""), radix) {
+        Ok(int) => Ok((rem, int)),
+        Err(_) => Err(nom::Err::Failure(new_error(i, ParseErr::IntOverflow))),
+    }
+}
+
+/// A float literal: `1.5`, `.5`, `5.`, `1e-9` or `2.5E3`, with an optional sign.
+/// `5.` only counts as a float when it isn't followed by a method call like `5.abs()`.
+pub fn float(i: &str) -> IRes<'_, f64> {
+    let exponent = || tuple((one_of("eE"), opt(one_of("+-")), digits(10)));
+    let (i, sign) = sp(opt(one_of("+-")))(i)?;
+    let (rem, consumed) = err(
+        sp(alt((
+            recognize(tuple((digits(10), char('.'), digits(10), opt(exponent())))),
+            recognize(tuple((char('.'), digits(10), opt(exponent())))),
+            recognize(pair(digits(10), exponent())),
+            recognize(terminated(
+                digits(10),
+                pair(char('.'), not(satisfy(|c| is_ident_char(c) || c == '.'))),
+            )),
+        ))),
+        ParseErr::Float,
+    )(i)?;
+    let float: f64 = consumed
+        .replace('_', "")
+        .parse()
+        .unwrap_or_else(|e| panic!("{e} : {consumed:?}"));
+    Ok((rem, if sign == Some('-') { -float } else { float }))
+}
@@ -1,18 +1,19 @@
 #[allow(clippy::wildcard_imports)]
 use super::*;
 
-pub fn statement(input: &str) -> IRes {
+pub fn statement(input: &str) -> IRes<'_> {
     alt((
         if_statement,
         while_statement,
         for_loop,
         break_statement,
+        nonlocal_statement,
         return_statement,
         function_def,
         class_def,
     ))(input)
 }
-fn function_def(i: &str) -> IRes {
+fn function_def(i: &str) -> IRes<'_> {
     preceded(
         keyword_name("fn"),
         cut(tuple((
@@ -24,7 +25,7 @@ fn function_def(i: &str) -> IRes {
     .map(|(ident, params, block)| Node::FuncDef(ident, params, block))
     .parse(i)
 }
-fn class_def(input: &str) -> IRes {
+fn class_def(input: &str) -> IRes<'_> {
     preceded(
         keyword_name("class"),
         cut(map(
@@ -40,10 +41,10 @@ fn class_def(input: &str) -> IRes {
         )),
     )(input)
 }
-fn if_statement(input: &str) -> IRes {
+fn if_statement(input: &str) -> IRes<'_> {
     if_segment(input)
 }
-fn if_segment(input: &str) -> IRes {
+fn if_segment(input: &str) -> IRes<'_> {
     preceded(
         keyword_name("if"),
         cut(tuple((node_expr, block, opt(or_else_segment)))),
@@ -51,7 +52,7 @@ fn if_segment(input: &str) -> IRes {
     .map(|(expr, block, or_else)| Node::IfState(Arc::new(expr), block, or_else.map(Arc::new)))
     .parse(input)
 }
-fn or_else_segment(input: &str) -> IRes {
+fn or_else_segment(input: &str) -> IRes<'_> {
     alt((
         preceded(
             keyword_name("elif"),
@@ -63,12 +64,12 @@ fn or_else_segment(input: &str) -> IRes {
     .parse(input)
 }
 
-fn while_statement(i: &str) -> IRes {
+fn while_statement(i: &str) -> IRes<'_> {
     preceded(keyword_name("while"), cut(pair(node_expr, block)))
         .map(|(n1, n2)| Node::WhileLoop(Arc::new(n1), n2))
         .parse(i)
 }
-fn for_loop(i: &str) -> IRes {
+fn for_loop(i: &str) -> IRes<'_> {
     preceded(
         keyword_name("for"),
         cut(tuple((
@@ -80,11 +81,20 @@ fn for_loop(i: &str) -> IRes {
     .map(|(name, expr, block)| Node::ForLoop(name, Arc::new(expr), block))
     .parse(i)
 }
-fn break_statement(i: &str) -> IRes {
+fn break_statement(i: &str) -> IRes<'_> {
     let (rem, _) = pair(keyword_name("break"), cut(spar(';')))(i)?;
     Ok((rem, Node::BreakState))
 }
-fn return_statement(i: &str) -> IRes {
+fn nonlocal_statement(i: &str) -> IRes<'_> {
+    delimited(
+        keyword_name("nonlocal"),
+        cut(separated_list1(spar(','), sp(ident))),
+        cut(spar(';')),
+    )
+    .map(|names| Node::Nonlocal(names.into()))
+    .parse(i)
+}
+fn return_statement(i: &str) -> IRes<'_> {
     delimited(
         keyword_name("return"),
         opt(preceded(one_of(" \n"), node_expr)),
@@ -93,7 +103,7 @@ fn return_statement(i: &str) -> IRes {
     .map(|node| Node::ReturnState(Arc::new(node.unwrap_or(Node::Literal(Literal::Null)))))
     .parse(i)
 }
-pub fn keyword_name<'a>(name: &'static str) -> impl FnMut(&'a str) -> IRes<&'a str> {
+pub fn keyword_name<'a>(name: &'static str) -> impl FnMut(&'a str) -> IRes<'a, &'a str> {
     move |i: &'a str| {
         let (rem, output) = sp(tag(name))(i)?;
         if rem.starts_with(is_ident_char) {
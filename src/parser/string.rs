@@ -0,0 +1,212 @@
+#[allow(clippy::wildcard_imports)]
+use super::*;
+use nom::Offset;
+
+/// A string literal: `"..."`, `'...'`, a triple quoted `"""..."""` (dedented, see [`dedent`])
+/// or a raw `r"..."` which takes its contents literally.
+pub fn string(i: &str) -> IRes<'_, String> {
+    let raw = i.starts_with('r');
+    let start = usize::from(raw);
+    let quote = match i[start..].chars().next() {
+        Some(quote @ ('"' | '\'')) => quote,
+        _ => return Err(nom::Err::Error(new_error(i, ParseErr::String))),
+    };
+    let triple = if quote == '"' { "\"\"\"" } else { "'''" };
+    if i[start..].starts_with(triple) {
+        let (rem, body) = string_body(&i[start + 3..], triple, !raw, i)?;
+        let output = dedent(i, body, !raw)?;
+        return Ok((rem, output));
+    }
+    let delimiter = &i[start..=start];
+    let (rem, body) = string_body(&i[start + 1..], delimiter, !raw, i)?;
+    let output = if raw {
+        body.to_string()
+    } else {
+        unescape(i, body)?
+    };
+    Ok((rem, output))
+}
+
+/// `f"a{x}b{y:>4}"` is desugared into `"a{0}b{1:>4}".format(x, y)`.
+pub fn fstring(i: &str) -> IRes<'_> {
+    let delimiter = match i.get(..2) {
+        Some("f\"") => "\"",
+        Some("f'") => "'",
+        _ => return Err(nom::Err::Error(new_error(i, ParseErr::FString))),
+    };
+    let (rem, body) = string_body(&i[2..], delimiter, true, i)?;
+    // Embedded expressions are parsed from `i` itself so errors point into the original input.
+    let after_body = &i[i.offset(body)..];
+    let fail = |at: usize| nom::Err::Failure(new_error(&after_body[at..], ParseErr::FString));
+    let mut template = String::with_capacity(body.len());
+    let mut args = vec![];
+    let bytes = body.as_bytes();
+    let (mut pos, mut literal_start) = (0, 0);
+    while pos < body.len() {
+        match (bytes[pos], bytes.get(pos + 1)) {
+            (b'\\', _) => pos += escape_len(&body[pos..]),
+            (brace @ (b'{' | b'}'), Some(next)) if *next == brace => {
+                push_literal(&mut template, i, &body[literal_start..pos])?;
+                template.push_str(if brace == b'{' { "{{" } else { "}}" });
+                pos += 2;
+                literal_start = pos;
+            }
+            (b'}', _) => return Err(fail(pos)),
+            (b'{', _) => {
+                push_literal(&mut template, i, &body[literal_start..pos])?;
+                let expr_input = &after_body[pos + 1..];
+                let (after, expr) = cut(bin_expr)(expr_input)?;
+                let mut end = pos + 1 + expr_input.len() - after.trim_start().len();
+                let spec = match body.get(end..=end) {
+                    Some("}") => "",
+                    Some(":") => {
+                        let Some(spec_end) = body[end..].find('}').map(|index| end + index) else {
+                            return Err(fail(end));
+                        };
+                        let spec = &body[end..spec_end];
+                        end = spec_end;
+                        spec
+                    }
+                    _ => return Err(fail(end)),
+                };
+                template.push_str(&format!("{{{}{spec}}}", args.len()));
+                args.push(expr);
+                pos = end + 1;
+                literal_start = pos;
+            }
+            _ => pos += 1,
+        }
+    }
+    push_literal(&mut template, i, &body[literal_start..])?;
+    let template = Node::literal(&*template);
+    let format = Node::FuncCall("format".into(), args.into());
+    Ok((rem, Node::bin_expr(BinOp::GetItem, template, format)))
+}
+
+/// Appends a literal part of an f-string, escaping braces that came from escape sequences.
+fn push_literal<'a>(
+    template: &mut String,
+    input: &'a str,
+    literal: &'a str,
+) -> Result<(), nom::Err<NomErr<'a>>> {
+    let literal = unescape(input, literal)?;
+    template.push_str(&literal.replace('{', "{{").replace('}', "}}"));
+    Ok(())
+}
+
+/// Splits off everything up to the closing `delimiter`, skipping over escaped characters
+/// when `escapes` is set. `literal` is the start of the whole literal, for error reporting.
+fn string_body<'a>(
+    input: &'a str,
+    delimiter: &str,
+    escapes: bool,
+    literal: &'a str,
+) -> IRes<'a, &'a str> {
+    let mut pos = 0;
+    while pos < input.len() {
+        if input[pos..].starts_with(delimiter) {
+            return Ok((&input[pos + delimiter.len()..], &input[..pos]));
+        }
+        pos += match input.as_bytes()[pos] {
+            b'\\' if escapes => escape_len(&input[pos..]),
+            _ => input[pos..].chars().next().map_or(1, char::len_utf8),
+        };
+    }
+    Err(nom::Err::Failure(new_error(literal, ParseErr::String)))
+}
+
+/// The length in bytes of the escape sequence at the start of `input`.
+fn escape_len(input: &str) -> usize {
+    let mut chars = input[1..].chars();
+    match chars.next() {
+        Some('u') if chars.next() == Some('{') => {
+            input.find('}').map_or(input.len(), |end| end + 1)
+        }
+        Some(char) => 1 + char.len_utf8(),
+        None => 1,
+    }
+}
+
+/// Resolves the escape sequences in `segment`, which must be a slice of `input`.
+fn unescape<'a>(input: &'a str, segment: &'a str) -> Result<String, nom::Err<NomErr<'a>>> {
+    let mut output = String::with_capacity(segment.len());
+    let mut chars = segment.char_indices();
+    while let Some((index, char)) = chars.next() {
+        if char != '\\' {
+            output.push(char);
+            continue;
+        }
+        let fail = || {
+            nom::Err::Failure(new_error(
+                &input[input.offset(&segment[index..])..],
+                ParseErr::Escape,
+            ))
+        };
+        let escaped = match chars.next().map(|(_, char)| char) {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some('\\') => '\\',
+            Some('"') => '"',
+            Some('\'') => '\'',
+            Some('u') => {
+                let rest = &segment[index + 2..];
+                let hex = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .map(|(hex, _)| hex)
+                    .filter(|hex| (1..=6).contains(&hex.len()))
+                    .ok_or_else(fail)?;
+                let code = u32::from_str_radix(hex, 16).map_err(|_| fail())?;
+                let escaped = char::from_u32(code).ok_or_else(fail)?;
+                // Skip past `{hex}`.
+                chars.nth(hex.len() + 1);
+                escaped
+            }
+            _ => return Err(fail()),
+        };
+        output.push(escaped);
+    }
+    Ok(output)
+}
+
+/// Triple quoted strings drop a newline directly after the opening quotes and remove
+/// the indentation shared by every non-blank line, so they can be indented with the code.
+fn dedent<'a>(
+    input: &'a str,
+    body: &'a str,
+    escapes: bool,
+) -> Result<String, nom::Err<NomErr<'a>>> {
+    let body = body
+        .strip_prefix("\r\n")
+        .or_else(|| body.strip_prefix('\n'))
+        .unwrap_or(body);
+    let is_blank = |line: &str| {
+        line.trim_start_matches([' ', '\t'])
+            .trim_end_matches('\r')
+            .is_empty()
+    };
+    let indent = body
+        .split('\n')
+        .filter(|line| !is_blank(line))
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    let mut output = String::with_capacity(body.len());
+    for (index, line) in body.split('\n').enumerate() {
+        if index != 0 {
+            output.push('\n');
+        }
+        if is_blank(line) {
+            continue;
+        }
+        let line = &line[indent..];
+        if escapes {
+            output.push_str(&unescape(input, line)?);
+        } else {
+            output.push_str(line);
+        }
+    }
+    Ok(output)
+}
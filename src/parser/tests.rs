@@ -2,7 +2,7 @@
 mod parser_tests {
     use std::sync::Arc;
 
-    use super::super::{bin_expr, parse, BinOp, Node, UnaryOp};
+    use super::super::{bin_expr, parse, BinOp, Literal, Node, UnaryOp};
     fn assert_expected(source: &str, expected: Vec<Node>) {
         let output = parse(source).unwrap();
         assert_eq!(output, Node::Globals(expected.into()));
@@ -326,14 +326,149 @@ mod parser_tests {
         );
         assert_expected(source, vec![expected]);
     }
+
+    #[test]
+    fn test_fstring() {
+        let source = r#"f"{{x}} = {x + 1:>4} {name}";"#;
+        let expected = Node::bin_expr(
+            BinOp::GetItem,
+            Node::literal("{{x}} = {0:>4} {1}"),
+            Node::func_call(
+                "format",
+                vec![
+                    Node::bin_expr(BinOp::Add, Node::ident("x"), Node::literal(1)),
+                    Node::ident("name"),
+                ],
+            ),
+        );
+        assert_expected(source, vec![expected]);
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let source = r#"a = "tab\t \"quoted\" \\ \u{1F600}"; b = 'it\'s "fine"';"#;
+        let expected = vec![
+            Node::set_eq("a", Node::literal("tab\t \"quoted\" \\ \u{1F600}")),
+            Node::set_eq("b", Node::literal("it's \"fine\"")),
+        ];
+        assert_expected(source, expected);
+    }
+
+    #[test]
+    fn test_raw_string() {
+        let source = r#"r"\d+\n";"#;
+        assert_expected(source, vec![Node::literal(r"\d+\n")]);
+    }
+
+    #[test]
+    fn test_triple_quoted_string() {
+        let source =
+            "text = \"\"\"\n    first\n      second\\t\n\n    third \"quotes\"\n    \"\"\";";
+        let expected = Node::literal("first\n  second\t\n\nthird \"quotes\"\n");
+        assert_expected(source, vec![Node::set_eq("text", expected)]);
+    }
+
+    #[test]
+    fn test_yield() {
+        let source = "fn gen() { sent = yield x + 1; yield; }";
+        let expected = Node::func_def(
+            "gen",
+            vec![],
+            vec![
+                Node::set_eq(
+                    "sent",
+                    Node::yield_expr(Node::bin_expr(
+                        BinOp::Add,
+                        Node::ident("x"),
+                        Node::literal(1),
+                    )),
+                ),
+                Node::yield_expr(Node::literal(Literal::Null)),
+            ],
+        );
+        assert_expected(source, vec![expected]);
+    }
+
+    #[test]
+    fn test_nonlocal() {
+        let source = "fn inc() { nonlocal count, total; count = count + 1; }";
+        let expected = Node::func_def(
+            "inc",
+            vec![],
+            vec![
+                Node::nonlocal(vec!["count", "total"]),
+                Node::set_eq(
+                    "count",
+                    Node::bin_expr(BinOp::Add, Node::ident("count"), Node::literal(1)),
+                ),
+            ],
+        );
+        assert_expected(source, vec![expected]);
+    }
+
+    #[test]
+    fn test_try_operator() {
+        let source = "a.get(0)?.len()?;";
+        let get = Node::bin_expr(
+            BinOp::GetItem,
+            Node::ident("a"),
+            Node::func_call("get", vec![Node::literal(0)]),
+        );
+        let len = Node::bin_expr(
+            BinOp::GetItem,
+            Node::try_expr(get),
+            Node::func_call("len", vec![]),
+        );
+        assert_expected(source, vec![Node::try_expr(len)]);
+    }
+
+    #[test]
+    fn test_numeric_literals() {
+        let source = "0xff_ff; 0o17; 0b1010; 1_000_000; 1e-9; 2.5E3; .5; 5.; 1_0.5;";
+        let expected = vec![
+            Node::literal(0xffff),
+            Node::literal(0o17),
+            Node::literal(0b1010),
+            Node::literal(1_000_000),
+            Node::literal(1e-9),
+            Node::literal(2.5e3),
+            Node::literal(0.5),
+            Node::literal(5.0),
+            Node::literal(10.5),
+        ];
+        assert_expected(source, expected);
+    }
 }
 
 #[cfg(test)]
 mod failing_tests {
+    use nom_supreme::error::ErrorTree;
+
     use super::super::parse;
     #[test]
     fn fail_get_item() {
         let source = "1.1;";
         parse(source).unwrap();
     }
+    #[test]
+    fn fail_fstring() {
+        assert!(parse(r#"f"{x";"#).is_err());
+        assert!(parse(r#"f"x}";"#).is_err());
+        assert!(parse(r#"f"{x y}";"#).is_err());
+    }
+    #[test]
+    fn fail_invalid_escape() {
+        let Err(ErrorTree::Base { location, .. }) = parse(r#"x = "ok \q";"#) else {
+            panic!("expected a single error");
+        };
+        assert!(location.starts_with(r"\q"));
+        assert!(parse(r#""\u{110000}";"#).is_err());
+        assert!(parse(r#""unterminated;"#).is_err());
+    }
+    #[test]
+    fn fail_int_literal() {
+        assert!(parse("x = 170141183460469231731687303715884105728;").is_err());
+        assert!(parse("0x;").is_err());
+        assert!(parse("0b102;").is_err());
+    }
 }
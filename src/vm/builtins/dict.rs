@@ -0,0 +1,161 @@
+use crate::vm::prelude::*;
+use indexmap::IndexMap;
+
+pub type DictMap = IndexMap<Arc<str>, PettyObject>;
+
+/// A string keyed dictionary which remembers insertion order.
+#[derive(Clone)]
+pub struct PtyDict(pub Arc<Counted<Mutex<DictMap>>>);
+
+impl PtyDict {
+    pub fn new(map: DictMap) -> Self {
+        Self(Counted::new(Mutex::new(map)).into())
+    }
+    fn key_objects(&self) -> Vec<PettyObject> {
+        let dict = self.0.lock().unwrap();
+        dict.keys().map(|key| PtyStr(key.clone()).into()).collect()
+    }
+}
+
+impl PettyObjectType for PtyDict {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, str: &str) -> PettyObject {
+        match str {
+            "len" | "__len__" => LEN.clone(),
+            "get" => GET.clone(),
+            "set" | "insert" | "__set_index__" => SET.clone(),
+            "__get_index__" => __GET_INDEX__.clone(),
+            "remove" => REMOVE.clone(),
+            "contains" | "contains_key" => CONTAINS.clone(),
+            "keys" => KEYS.clone(),
+            "values" => VALUES.clone(),
+            "__repr__" => __REPR__.clone(),
+            "__bool__" => __BOOL__.clone(),
+            "__iter__" | "iter" => __ITER__.clone(),
+            "__is_eq__" => __IS_EQ__.clone(),
+            _ => panic!("dict has no attribute {str}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("dict is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn static_items(&self) -> bool {
+        true
+    }
+}
+
+impl fmt::Display for PtyDict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (index, (key, value)) in self.0.lock().unwrap().iter().enumerate() {
+            let seperator = if index == 0 { "" } else { ", " };
+            write!(f, "{seperator}{key}: {value}")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[pettymethod]
+pub fn dict() -> PtyDict {
+    PtyDict::new(DictMap::new())
+}
+
+#[pettymethod]
+#[allow(clippy::cast_precision_loss)]
+fn len(this: &PtyDict) -> PtyNum {
+    PtyNum(this.0.lock().unwrap().len() as f64)
+}
+
+#[pettymethod]
+fn get(this: &PtyDict, key: &PtyStr) -> Option<PettyObject> {
+    this.0.lock().unwrap().get(&key.0).cloned()
+}
+
+#[pettymethod]
+fn __get_index__(this: &PtyDict, key: &PtyStr) -> PettyObject {
+    let dict = this.0.lock().unwrap();
+    let value = dict
+        .get(&key.0)
+        .unwrap_or_else(|| panic!("Key not found: {key}"));
+    value.clone()
+}
+
+#[pettymethod]
+fn set(this: &PtyDict, key: &PtyStr, value: &PettyObject, vm: &mut Vm) {
+    if !this.0.lock().unwrap().contains_key(&key.0) {
+        vm.grow_dict(this, 1);
+    }
+    this.0.lock().unwrap().insert(key.0.clone(), value.clone());
+}
+
+#[pettymethod]
+fn remove(this: &PtyDict, key: &PtyStr, vm: &mut Vm) -> Option<PettyObject> {
+    let removed = this.0.lock().unwrap().shift_remove(&key.0);
+    if removed.is_some() {
+        vm.shrink_dict(this, 1);
+    }
+    removed
+}
+
+#[pettymethod]
+fn contains(this: &PtyDict, key: &PtyStr) -> PettyObject {
+    PtyBool::new(this.0.lock().unwrap().contains_key(&key.0))
+}
+
+#[pettymethod]
+fn keys(this: &PtyDict, vm: &mut Vm) -> PtyList {
+    vm.new_list(this.key_objects())
+}
+
+#[pettymethod]
+fn values(this: &PtyDict, vm: &mut Vm) -> PtyList {
+    let values = this.0.lock().unwrap().values().cloned().collect();
+    vm.new_list(values)
+}
+
+#[pettymethod]
+fn __repr__(this: &PtyDict, vm: &mut Vm) -> PtyStr {
+    let items: Vec<_> = this.0.lock().unwrap().clone().into_iter().collect();
+    let mut string = String::from("{");
+    for (index, (key, value)) in items.iter().enumerate() {
+        let seperator = if index == 0 { "" } else { ", " };
+        string.push_str(seperator);
+        string.push_str(key);
+        string.push_str(": ");
+        string.push_str(&value.force_repr(vm).0);
+    }
+    string.push('}');
+    PtyStr(string.into())
+}
+
+#[pettymethod]
+fn __bool__(this: &PtyDict) -> PettyObject {
+    PtyBool::new(!this.0.lock().unwrap().is_empty())
+}
+
+#[pettymethod]
+fn __iter__(this: &PtyDict, vm: &mut Vm) -> PtyListIter {
+    PtyListIter(vm.new_list(this.key_objects()).0, Mutex::new(0).into())
+}
+
+#[pettymethod]
+fn __is_eq__(lhs: &PtyDict, rhs: &PtyDict, vm: &mut Vm) -> PettyObject {
+    let lhs = lhs.0.lock().unwrap().clone();
+    let rhs = rhs.0.lock().unwrap().clone();
+    if lhs.len() != rhs.len() {
+        return FALSE.clone();
+    }
+    for (key, lhs) in &lhs {
+        let Some(rhs) = rhs.get(key) else {
+            return FALSE.clone();
+        };
+        let is_eq = lhs.call_method(vm, "__is_eq__", FuncArgs(&[lhs, rhs]));
+        let is_eq = is_eq.downcast::<PtyBool>().expect("Expected Bool");
+        if !is_eq.0 {
+            return FALSE.clone();
+        }
+    }
+    TRUE.clone()
+}
@@ -0,0 +1,247 @@
+//! The formatting engine behind `str.format` and f-strings.
+//!
+//! Placeholders are `{}` (the next positional argument), `{0}` (by position) or `{name}`
+//! (looked up in a dict argument), optionally followed by `:spec`, where `spec` is
+//! `[[fill]align][sign][#][0][width][.precision][type]` as in Python.
+//! `{{` and `}}` produce literal braces.
+use std::fmt::Write;
+
+use crate::vm::prelude::*;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+    /// Pads between the sign and the digits.
+    AfterSign,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Sign {
+    Negative,
+    Always,
+    Space,
+}
+
+struct FormatSpec {
+    fill: char,
+    align: Option<Align>,
+    sign: Sign,
+    alternate: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+/// # Panics
+/// On malformed templates, missing arguments or a spec that doesn't suit its argument.
+pub fn format(vm: &mut Vm, template: &str, args: &[&PettyObject]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut next_positional = 0;
+    let mut chars = template.char_indices().peekable();
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '{' if chars.next_if(|&(_, char)| char == '{').is_some() => output.push('{'),
+            '}' if chars.next_if(|&(_, char)| char == '}').is_some() => output.push('}'),
+            '}' => panic!("Unmatched '}}' in format string {template:?}"),
+            '{' => {
+                let rest = &template[index + 1..];
+                let end = rest
+                    .find('}')
+                    .unwrap_or_else(|| panic!("Unmatched '{{' in format string {template:?}"));
+                let field = &rest[..end];
+                while chars.next_if(|&(i, _)| i <= index + end + 1).is_some() {}
+
+                let (name, spec) = field.split_once(':').unwrap_or((field, ""));
+                let value = if name.is_empty() {
+                    next_positional += 1;
+                    positional(args, next_positional - 1)
+                } else if let Ok(index) = name.parse() {
+                    positional(args, index)
+                } else {
+                    named(args, name)
+                };
+                let spec = parse_spec(spec);
+                // Padding and precision are only bounded by the numbers in the spec.
+                let padded = spec.width.saturating_add(spec.precision.unwrap_or(0));
+                vm.check_memory(output.len().saturating_add(padded));
+                format_value(vm, &mut output, &value, &spec);
+            }
+            char => output.push(char),
+        }
+    }
+    output
+}
+
+fn positional(args: &[&PettyObject], index: usize) -> PettyObject {
+    let value = args.get(index);
+    (*value.unwrap_or_else(|| panic!("No argument at position {index}"))).clone()
+}
+
+fn named(args: &[&PettyObject], name: &str) -> PettyObject {
+    for arg in args {
+        if let Some(PtyDict(dict)) = arg.downcast_ref() {
+            if let Some(value) = dict.lock().unwrap().get(name) {
+                return value.clone();
+            }
+        }
+    }
+    panic!("No argument named {name}");
+}
+
+fn parse_spec(spec: &str) -> FormatSpec {
+    let mut format = FormatSpec {
+        fill: ' ',
+        align: None,
+        sign: Sign::Negative,
+        alternate: false,
+        width: 0,
+        precision: None,
+        kind: None,
+    };
+    let mut chars: Vec<char> = spec.chars().collect();
+    chars.reverse();
+    let align = |char: char| match char {
+        '<' => Some(Align::Left),
+        '>' => Some(Align::Right),
+        '^' => Some(Align::Center),
+        '=' => Some(Align::AfterSign),
+        _ => None,
+    };
+
+    if let Some(aligned) = chars
+        .get(chars.len().wrapping_sub(2))
+        .copied()
+        .and_then(align)
+    {
+        format.fill = chars.pop().unwrap();
+        format.align = Some(aligned);
+        chars.pop();
+    } else if let Some(aligned) = chars.last().copied().and_then(align) {
+        format.align = Some(aligned);
+        chars.pop();
+    }
+    let sign = match chars.last() {
+        Some('+') => Some(Sign::Always),
+        Some(' ') => Some(Sign::Space),
+        Some('-') => Some(Sign::Negative),
+        _ => None,
+    };
+    if let Some(sign) = sign {
+        format.sign = sign;
+        chars.pop();
+    }
+    if chars.last() == Some(&'#') {
+        format.alternate = true;
+        chars.pop();
+    }
+    if chars.last() == Some(&'0') {
+        if format.align.is_none() {
+            format.fill = '0';
+            format.align = Some(Align::AfterSign);
+        }
+        chars.pop();
+    }
+    format.width = take_number(&mut chars).unwrap_or(0);
+    if chars.last() == Some(&'.') {
+        chars.pop();
+        let precision = take_number(&mut chars);
+        format.precision =
+            Some(precision.unwrap_or_else(|| panic!("Missing precision in {spec:?}")));
+    }
+    format.kind = chars.pop();
+    assert!(chars.is_empty(), "Invalid format spec {spec:?}");
+    format
+}
+
+fn take_number(chars: &mut Vec<char>) -> Option<usize> {
+    let mut number = None;
+    while let Some(digit) = chars.last().and_then(|char| char.to_digit(10)) {
+        number = Some(number.unwrap_or(0) * 10 + digit as usize);
+        chars.pop();
+    }
+    number
+}
+
+fn format_value(vm: &mut Vm, output: &mut String, value: &PettyObject, spec: &FormatSpec) {
+    if let Some(PtyNum(num)) = value.downcast_ref() {
+        let (sign, body) = format_number(*num, spec);
+        return pad(output, &sign, &body, spec, Align::Right);
+    }
+    assert!(
+        spec.kind.is_none_or(|kind| kind == 's'),
+        "Format type {:?} requires a number, got {value}",
+        spec.kind.unwrap_or_default()
+    );
+    let repr = value.force_repr(vm).0;
+    let body: String = match spec.precision {
+        Some(precision) => repr.chars().take(precision).collect(),
+        None => repr.to_string(),
+    };
+    pad(output, "", &body, spec, Align::Left);
+}
+
+/// Splits a formatted number into its sign (and radix prefix) and its digits.
+fn format_number(num: f64, spec: &FormatSpec) -> (String, String) {
+    let mut sign = match spec.sign {
+        _ if num < 0.0 => "-",
+        Sign::Always => "+",
+        Sign::Space => " ",
+        Sign::Negative => "",
+    }
+    .to_string();
+    let abs = num.abs();
+    let body = match (spec.kind, spec.precision) {
+        (None | Some('s'), None) => abs.to_string(),
+        (None | Some('f' | 'F'), precision) => format!("{abs:.*}", precision.unwrap_or(6)),
+        (Some('%'), precision) => format!("{:.*}%", precision.unwrap_or(6), abs * 100.0),
+        (Some(kind @ ('e' | 'E')), precision) => {
+            let formatted = format!("{abs:.*e}", precision.unwrap_or(6));
+            let (mantissa, exponent) = formatted.split_once('e').unwrap();
+            let exponent: i32 = exponent.parse().unwrap();
+            let exponent_sign = if exponent < 0 { '-' } else { '+' };
+            let mut body = format!("{mantissa}e{exponent_sign}{:02}", exponent.abs());
+            if kind == 'E' {
+                body = body.to_uppercase();
+            }
+            body
+        }
+        (Some(kind @ ('d' | 'x' | 'X' | 'o' | 'b')), _) => {
+            assert!(
+                abs.fract() == 0.0 && abs.is_finite(),
+                "Format type {kind:?} requires an integer, got {num}"
+            );
+            #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+            let int = abs as u128;
+            let (body, prefix) = match kind {
+                'd' => (int.to_string(), ""),
+                'x' => (format!("{int:x}"), "0x"),
+                'X' => (format!("{int:X}"), "0X"),
+                'o' => (format!("{int:o}"), "0o"),
+                _ => (format!("{int:b}"), "0b"),
+            };
+            if spec.alternate {
+                sign.push_str(prefix);
+            }
+            body
+        }
+        (Some(kind), _) => panic!("Unknown format type {kind:?}"),
+    };
+    (sign, body)
+}
+
+fn pad(output: &mut String, sign: &str, body: &str, spec: &FormatSpec, default: Align) {
+    let len = sign.chars().count() + body.chars().count();
+    let padding = spec.width.saturating_sub(len);
+    let fill = |count: usize| spec.fill.to_string().repeat(count);
+    let _ = match spec.align.unwrap_or(default) {
+        Align::Left => write!(output, "{sign}{body}{}", fill(padding)),
+        Align::Right => write!(output, "{}{sign}{body}", fill(padding)),
+        Align::AfterSign => write!(output, "{sign}{}{body}", fill(padding)),
+        Align::Center => {
+            let left = padding / 2;
+            write!(output, "{}{sign}{body}{}", fill(left), fill(padding - left))
+        }
+    };
+}
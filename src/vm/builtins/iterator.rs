@@ -0,0 +1,340 @@
+use crate::vm::prelude::*;
+
+/// Where an [`PtyIterator`] pulls its next item from.
+/// Adapters hold the iterator they wrap, so nothing is evaluated until `__next__` is called.
+enum Source {
+    /// Any object implementing `__next__`, along with that method.
+    Next(PettyObject, PettyObject),
+    Map(PtyIterator, PettyObject),
+    Filter(PtyIterator, PettyObject),
+    Take(PtyIterator, usize),
+    Skip(PtyIterator, usize),
+    TakeWhile(PtyIterator, PettyObject, bool),
+    Chain(PtyIterator, PtyIterator),
+    Zip(PtyIterator, PtyIterator),
+    Enumerate(PtyIterator, usize),
+    StepBy(PtyIterator, usize, bool),
+}
+
+/// A lazy iterator, returned by `iter(x)` and by the combinators every iterator shares.
+#[derive(Clone)]
+pub struct PtyIterator(Arc<Mutex<Source>>);
+
+impl PtyIterator {
+    fn new(source: Source) -> Self {
+        Self(Mutex::new(source).into())
+    }
+    /// Calls `__iter__` on `iterable` and wraps the resulting `__next__` source.
+    /// Iterators return themselves from `__iter__`, so they are advanced in place.
+    pub fn from_iterable(vm: &mut Vm, iterable: &PettyObject) -> Self {
+        if let Some(iter) = iterable.downcast_ref::<PtyIterator>() {
+            return iter.clone();
+        }
+        let iter = iterable.call_method(vm, "__iter__", FuncArgs(&[iterable]));
+        if let Some(iter) = iter.downcast_ref::<PtyIterator>() {
+            return iter.clone();
+        }
+        let get_next = iter.get_item(vm, &iter, "__next__");
+        Self::new(Source::Next(iter, get_next))
+    }
+    /// Only the bookkeeping happens under the lock. It is released before calling into
+    /// scripts, which may advance this same iterator from inside a `map` or `filter`.
+    pub fn next(&self, vm: &mut Vm) -> Option<PettyObject> {
+        let mut source = self.0.lock().unwrap();
+        match &mut *source {
+            Source::Next(iter, get_next) => {
+                let (iter, get_next) = (iter.clone(), get_next.clone());
+                drop(source);
+                let next = get_next.call(vm, &get_next, FuncArgs(&[&iter]));
+                next.downcast::<PtyOption>().expect("Expected Option").0
+            }
+            Source::Map(iter, func) => {
+                let (iter, func) = (iter.clone(), func.clone());
+                drop(source);
+                let next = iter.next(vm)?;
+                Some(func.call(vm, &func, FuncArgs(&[&next])))
+            }
+            Source::Filter(iter, func) => {
+                let (iter, func) = (iter.clone(), func.clone());
+                drop(source);
+                loop {
+                    let next = iter.next(vm)?;
+                    if predicate(vm, &func, &next) {
+                        return Some(next);
+                    }
+                }
+            }
+            Source::Take(iter, remaining) => {
+                if *remaining == 0 {
+                    return None;
+                }
+                *remaining -= 1;
+                let iter = iter.clone();
+                drop(source);
+                iter.next(vm)
+            }
+            Source::Skip(iter, remaining) => {
+                let (iter, remaining) = (iter.clone(), std::mem::take(remaining));
+                drop(source);
+                for _ in 0..remaining {
+                    iter.next(vm)?;
+                }
+                iter.next(vm)
+            }
+            Source::TakeWhile(iter, func, done) => {
+                if *done {
+                    return None;
+                }
+                let (iter, func) = (iter.clone(), func.clone());
+                drop(source);
+                let next = iter.next(vm)?;
+                if predicate(vm, &func, &next) {
+                    return Some(next);
+                }
+                if let Source::TakeWhile(_, _, done) = &mut *self.0.lock().unwrap() {
+                    *done = true;
+                }
+                None
+            }
+            Source::Chain(first, second) => {
+                let (first, second) = (first.clone(), second.clone());
+                drop(source);
+                first.next(vm).or_else(|| second.next(vm))
+            }
+            Source::Zip(lhs, rhs) => {
+                let (lhs, rhs) = (lhs.clone(), rhs.clone());
+                drop(source);
+                let lhs = lhs.next(vm)?;
+                let rhs = rhs.next(vm)?;
+                Some(vm.new_list(vec![lhs, rhs]).into())
+            }
+            Source::Enumerate(iter, _) => {
+                let iter = iter.clone();
+                drop(source);
+                let next = iter.next(vm)?;
+                let Source::Enumerate(_, index) = &mut *self.0.lock().unwrap() else {
+                    unreachable!()
+                };
+                #[allow(clippy::cast_precision_loss)]
+                let pair = vec![PtyNum(*index as f64).into(), next];
+                *index += 1;
+                Some(vm.new_list(pair).into())
+            }
+            Source::StepBy(iter, step, started) => {
+                let skipped = if *started { *step - 1 } else { 0 };
+                *started = true;
+                let iter = iter.clone();
+                drop(source);
+                for _ in 0..skipped {
+                    iter.next(vm)?;
+                }
+                iter.next(vm)
+            }
+        }
+    }
+}
+
+/// The combinators shared by every iterator, looked up by the other iterator types
+/// so `range(10).map(f)` works without calling `iter` first.
+pub fn method(key: &str) -> Option<PettyObject> {
+    let method = match key {
+        "map" => MAP.clone(),
+        "filter" => FILTER.clone(),
+        "take" => TAKE.clone(),
+        "skip" => SKIP.clone(),
+        "take_while" => TAKE_WHILE.clone(),
+        "chain" => CHAIN.clone(),
+        "zip" => ZIP.clone(),
+        "enumerate" => ENUMERATE.clone(),
+        "step_by" => STEP_BY.clone(),
+        "collect" => COLLECT.clone(),
+        "count" => COUNT.clone(),
+        "sum" => SUM.clone(),
+        "product" => PRODUCT.clone(),
+        "fold" => FOLD.clone(),
+        _ => return None,
+    };
+    Some(method)
+}
+
+impl PettyObjectType for PtyIterator {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, str: &str) -> PettyObject {
+        match str {
+            "__next__" | "next" => __NEXT__.clone(),
+            "__iter__" | "iter" => __ITER__.clone(),
+            "__repr__" => __REPR__.clone(),
+            _ => method(str).unwrap_or_else(|| panic!("iterator has no attribute {str}")),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("iterator is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for PtyIterator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "iterator at {:?}", self as *const Self)
+    }
+}
+
+fn predicate(vm: &mut Vm, func: &PettyObject, item: &PettyObject) -> bool {
+    let output = func.call(vm, func, FuncArgs(&[item]));
+    PtyBool::truthy(vm, &output)
+}
+
+fn count_arg(num: PtyNum) -> usize {
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let count = num.0.max(0.0) as usize;
+    count
+}
+
+/// The `iter` builtin, turns any iterable into an [`PtyIterator`].
+#[pettymethod]
+pub fn iter(iterable: &PettyObject, vm: &mut Vm) -> PtyIterator {
+    PtyIterator::from_iterable(vm, iterable)
+}
+
+#[pettymethod]
+fn __next__(this: &PtyIterator, vm: &mut Vm) -> PettyObject {
+    PtyOption::new(this.next(vm))
+}
+
+#[pettymethod]
+fn __iter__(this: PtyIterator) -> PtyIterator {
+    this
+}
+
+#[pettymethod]
+fn __repr__(this: &PtyIterator) -> PtyStr {
+    PtyStr(format!("{this}").into())
+}
+
+#[pettymethod]
+fn map(this: &PettyObject, func: &PettyObject, vm: &mut Vm) -> PtyIterator {
+    PtyIterator::new(Source::Map(
+        PtyIterator::from_iterable(vm, this),
+        func.clone(),
+    ))
+}
+
+#[pettymethod]
+fn filter(this: &PettyObject, func: &PettyObject, vm: &mut Vm) -> PtyIterator {
+    PtyIterator::new(Source::Filter(
+        PtyIterator::from_iterable(vm, this),
+        func.clone(),
+    ))
+}
+
+#[pettymethod]
+fn take(this: &PettyObject, count: PtyNum, vm: &mut Vm) -> PtyIterator {
+    PtyIterator::new(Source::Take(
+        PtyIterator::from_iterable(vm, this),
+        count_arg(count),
+    ))
+}
+
+#[pettymethod]
+fn skip(this: &PettyObject, count: PtyNum, vm: &mut Vm) -> PtyIterator {
+    PtyIterator::new(Source::Skip(
+        PtyIterator::from_iterable(vm, this),
+        count_arg(count),
+    ))
+}
+
+#[pettymethod]
+fn take_while(this: &PettyObject, func: &PettyObject, vm: &mut Vm) -> PtyIterator {
+    let iter = PtyIterator::from_iterable(vm, this);
+    PtyIterator::new(Source::TakeWhile(iter, func.clone(), false))
+}
+
+#[pettymethod]
+fn chain(this: &PettyObject, other: &PettyObject, vm: &mut Vm) -> PtyIterator {
+    let first = PtyIterator::from_iterable(vm, this);
+    let second = PtyIterator::from_iterable(vm, other);
+    PtyIterator::new(Source::Chain(first, second))
+}
+
+/// Yields `[lhs, rhs]` pairs until either side runs out.
+#[pettymethod]
+fn zip(this: &PettyObject, other: &PettyObject, vm: &mut Vm) -> PtyIterator {
+    let lhs = PtyIterator::from_iterable(vm, this);
+    let rhs = PtyIterator::from_iterable(vm, other);
+    PtyIterator::new(Source::Zip(lhs, rhs))
+}
+
+/// Yields `[index, item]` pairs.
+#[pettymethod]
+fn enumerate(this: &PettyObject, vm: &mut Vm) -> PtyIterator {
+    PtyIterator::new(Source::Enumerate(PtyIterator::from_iterable(vm, this), 0))
+}
+
+#[pettymethod]
+fn step_by(this: &PettyObject, step: PtyNum, vm: &mut Vm) -> PtyIterator {
+    let step = count_arg(step);
+    assert!(step > 0, "step_by: step must be greater than 0");
+    PtyIterator::new(Source::StepBy(
+        PtyIterator::from_iterable(vm, this),
+        step,
+        false,
+    ))
+}
+
+#[pettymethod]
+fn collect(this: &PettyObject, vm: &mut Vm) -> PtyList {
+    let iter = PtyIterator::from_iterable(vm, this);
+    let mut items = Vec::new();
+    while let Some(next) = iter.next(vm) {
+        vm.check_memory((items.len() + 1) * std::mem::size_of::<PettyObject>());
+        items.push(next);
+    }
+    vm.new_list(items)
+}
+
+#[pettymethod]
+fn count(this: &PettyObject, vm: &mut Vm) -> PtyNum {
+    let iter = PtyIterator::from_iterable(vm, this);
+    let mut count = 0.0;
+    while iter.next(vm).is_some() {
+        count += 1.0;
+    }
+    PtyNum(count)
+}
+
+/// Adds the items together with `__add__`, an empty iterator sums to `0`.
+#[pettymethod]
+fn sum(this: &PettyObject, vm: &mut Vm) -> PettyObject {
+    let iter = PtyIterator::from_iterable(vm, this);
+    let Some(mut sum) = iter.next(vm) else {
+        return PtyNum(0.0).into();
+    };
+    while let Some(next) = iter.next(vm) {
+        sum = sum.call_method(vm, "__add__", FuncArgs(&[&sum, &next]));
+    }
+    sum
+}
+
+/// Multiplies the items together with `__mul__`, an empty iterator gives `1`.
+#[pettymethod]
+fn product(this: &PettyObject, vm: &mut Vm) -> PettyObject {
+    let iter = PtyIterator::from_iterable(vm, this);
+    let Some(mut product) = iter.next(vm) else {
+        return PtyNum(1.0).into();
+    };
+    while let Some(next) = iter.next(vm) {
+        product = product.call_method(vm, "__mul__", FuncArgs(&[&product, &next]));
+    }
+    product
+}
+
+#[pettymethod]
+fn fold(this: &PettyObject, initial: &PettyObject, func: &PettyObject, vm: &mut Vm) -> PettyObject {
+    let iter = PtyIterator::from_iterable(vm, this);
+    let mut acc = initial.clone();
+    while let Some(next) = iter.next(vm) {
+        acc = func.call(vm, func, FuncArgs(&[&acc, &next]));
+    }
+    acc
+}
@@ -1,7 +1,9 @@
+use std::cmp::Ordering;
+
 use crate::vm::prelude::*;
 
 #[derive(Clone)]
-pub struct PtyList(pub Arc<Mutex<Vec<PettyObject>>>);
+pub struct PtyList(pub Arc<Counted<Mutex<Vec<PettyObject>>>>);
 
 impl PettyObjectType for PtyList {
     fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, str: &str) -> PettyObject {
@@ -11,7 +13,26 @@ impl PettyObjectType for PtyList {
             "get" | "__get_index__" => GET.clone(),
             "set" | "__set_index__" => SET.clone(),
             "contains" => CONTAINS.clone(),
-            "find" => FIND.clone(),
+            "find" | "index_of" => FIND.clone(),
+            "map" => MAP.clone(),
+            "filter" => FILTER.clone(),
+            "reduce" => REDUCE.clone(),
+            "any" => ANY.clone(),
+            "all" => ALL.clone(),
+            "sort" => SORT.clone(),
+            "reverse" => REVERSE.clone(),
+            "pop" => POP.clone(),
+            "insert" => INSERT.clone(),
+            "remove" => REMOVE.clone(),
+            "extend" => EXTEND.clone(),
+            "slice" => SLICE.clone(),
+            "clear" => CLEAR.clone(),
+            "copy" => COPY.clone(),
+            "min" => MIN.clone(),
+            "max" => MAX.clone(),
+            "sum" => SUM.clone(),
+            "enumerate" => ENUMERATE.clone(),
+            "zip" => ZIP.clone(),
             "__repr__" => __REPR__.clone(),
             "__add__" => __ADD__.clone(),
             "__mul__" => __MUL__.clone(),
@@ -27,6 +48,67 @@ impl PettyObjectType for PtyList {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+    fn static_items(&self) -> bool {
+        true
+    }
+}
+
+impl PtyList {
+    pub fn new(vec: Vec<PettyObject>) -> Self {
+        Self(Counted::new(Mutex::new(vec)).into())
+    }
+    /// A snapshot of the items, so callbacks are free to modify the list while we iterate.
+    fn items(&self) -> Vec<PettyObject> {
+        self.0.lock().unwrap().clone()
+    }
+    /// Resolves a possibly negative `index` against `len`, clamped to `0..=len`.
+    fn position(index: f64, len: usize) -> usize {
+        #[allow(clippy::cast_precision_loss)]
+        let index = if index < 0.0 {
+            index + len as f64
+        } else {
+            index
+        };
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let index = index.max(0.0) as usize;
+        index.min(len)
+    }
+    /// Collects a list or anything implementing `__iter__`.
+    pub fn collect(vm: &mut Vm, iterable: &PettyObject) -> Vec<PettyObject> {
+        if let Some(list) = iterable.downcast_ref::<PtyList>() {
+            return list.items();
+        }
+        let iter = iterable.call_method(vm, "__iter__", FuncArgs(&[iterable]));
+        let get_next = iter.get_item(vm, &iter, "__next__");
+        let mut items = Vec::new();
+        while let Some(next) = {
+            let next = get_next.call(vm, &get_next, FuncArgs(&[&iter]));
+            next.downcast::<PtyOption>().expect("Expected Option").0
+        } {
+            vm.check_memory((items.len() + 1) * std::mem::size_of::<PettyObject>());
+            items.push(next);
+        }
+        items
+    }
+}
+
+fn less_than(vm: &mut Vm, lhs: &PettyObject, rhs: &PettyObject) -> bool {
+    let bool = lhs.call_method(vm, "__lt__", FuncArgs(&[lhs, rhs]));
+    bool.downcast_ref::<PtyBool>().expect("Expected Bool").0
+}
+
+/// Numbers use `total_cmp` so NaN keys still give `sort_by` the total order it requires.
+fn compare(vm: &mut Vm, lhs: &PettyObject, rhs: &PettyObject) -> Ordering {
+    if let (Some(lhs), Some(rhs)) = (lhs.downcast_ref::<PtyNum>(), rhs.downcast_ref::<PtyNum>()) {
+        return lhs.0.total_cmp(&rhs.0);
+    }
+    if less_than(vm, lhs, rhs) {
+        Ordering::Less
+    } else if less_than(vm, rhs, lhs) {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
 }
 
 impl fmt::Display for PtyList {
@@ -62,7 +144,8 @@ fn set(this: &PtyList, index: PtyNum, obj: &PettyObject) {
 }
 
 #[pettymethod]
-fn push(this: &PtyList, obj: &PettyObject) {
+fn push(this: &PtyList, obj: &PettyObject, vm: &mut Vm) {
+    vm.grow_list(this, 1);
     this.0.lock().unwrap().push(obj.clone());
 }
 
@@ -114,24 +197,26 @@ fn __bool__(this: &PtyList) -> PettyObject {
 }
 
 #[pettymethod]
-fn __add__(lhs: &PtyList, rhs: &PtyList) -> PtyList {
+fn __add__(lhs: &PtyList, rhs: &PtyList, vm: &mut Vm) -> PtyList {
     let mut vec = { lhs.0.lock().unwrap().clone() };
     vec.extend_from_slice(&rhs.0.lock().unwrap());
 
-    PtyList(Mutex::new(vec).into())
+    vm.new_list(vec)
 }
 
 #[pettymethod]
-fn __mul__(lhs: &PtyList, rhs: PtyNum) -> PtyList {
+fn __mul__(lhs: &PtyList, rhs: PtyNum, vm: &mut Vm) -> PtyList {
     #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
     let repeat = rhs.0.max(0.0) as usize;
-    let mut vec = Vec::with_capacity(repeat * lhs.0.lock().unwrap().len());
+    let list = PtyList::new(Vec::new());
+    let items = lhs.items();
+    vm.grow_list(&list, repeat.saturating_mul(items.len()));
+    let mut vec = Vec::with_capacity(repeat * items.len());
     for _ in 0..repeat {
-        for obj in lhs.0.lock().unwrap().iter() {
-            vec.push(obj.clone());
-        }
+        vec.extend_from_slice(&items);
     }
-    PtyList(Mutex::new(vec).into())
+    *list.0.lock().unwrap() = vec;
+    list
 }
 
 #[pettymethod]
@@ -157,3 +242,229 @@ fn __is_eq__(lhs: &PtyList, rhs: &PtyList, vm: &mut Vm) -> PettyObject {
 fn __iter__(this: PtyList) -> PtyListIter {
     PtyListIter(this.0, Mutex::new(0).into())
 }
+
+#[pettymethod]
+fn map(this: &PtyList, func: &PettyObject, vm: &mut Vm) -> PtyList {
+    let items = this.items();
+    let items = items
+        .iter()
+        .map(|item| func.call(vm, func, FuncArgs(&[item])))
+        .collect();
+    vm.new_list(items)
+}
+
+#[pettymethod]
+fn filter(this: &PtyList, func: &PettyObject, vm: &mut Vm) -> PtyList {
+    let mut items = this.items();
+    items.retain(|item| {
+        let keep = func.call(vm, func, FuncArgs(&[item]));
+        PtyBool::truthy(vm, &keep)
+    });
+    vm.new_list(items)
+}
+
+/// `reduce(func[, initial])`, without an initial value the first item is used.
+#[pettymethod]
+fn reduce(this: &PtyList, func: &PettyObject, vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    let mut items = this.items().into_iter();
+    let initial = match args.0.first() {
+        Some(initial) => (*initial).clone(),
+        None => items
+            .next()
+            .expect("reduce of an empty list with no initial value"),
+    };
+    items.fold(initial, |acc, item| {
+        func.call(vm, func, FuncArgs(&[&acc, &item]))
+    })
+}
+
+/// `any([predicate])`, checks the items themselves when no predicate is given.
+#[pettymethod]
+fn any(this: &PtyList, vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    let predicate = args.0.first().copied();
+    let any = this.items().iter().any(|item| match predicate {
+        Some(func) => {
+            let output = func.call(vm, func, FuncArgs(&[item]));
+            PtyBool::truthy(vm, &output)
+        }
+        None => PtyBool::truthy(vm, item),
+    });
+    PtyBool::new(any)
+}
+
+/// `all([predicate])`, checks the items themselves when no predicate is given.
+#[pettymethod]
+fn all(this: &PtyList, vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    let predicate = args.0.first().copied();
+    let all = this.items().iter().all(|item| match predicate {
+        Some(func) => {
+            let output = func.call(vm, func, FuncArgs(&[item]));
+            PtyBool::truthy(vm, &output)
+        }
+        None => PtyBool::truthy(vm, item),
+    });
+    PtyBool::new(all)
+}
+
+/// Stable in place sort using `__lt__`.
+/// Accepts an optional key function (or `null`) followed by an optional `reverse` bool.
+#[pettymethod]
+fn sort(this: &PtyList, vm: &mut Vm, args: FuncArgs) {
+    let mut key = None;
+    let mut reverse = false;
+    for arg in args.0 {
+        if let Some(bool) = arg.downcast_ref::<PtyBool>() {
+            reverse = bool.0;
+        } else if arg.downcast_ref::<PtyNull>().is_none() {
+            key = Some(*arg);
+        }
+    }
+    let items = this.items();
+    let keys: Vec<PettyObject> = match key {
+        Some(func) => items
+            .iter()
+            .map(|item| func.call(vm, func, FuncArgs(&[item])))
+            .collect(),
+        None => items.clone(),
+    };
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&lhs, &rhs| {
+        // Swapping the operands keeps equal items in their original order.
+        let (lhs, rhs) = if reverse { (rhs, lhs) } else { (lhs, rhs) };
+        compare(vm, &keys[lhs], &keys[rhs])
+    });
+    *this.0.lock().unwrap() = order
+        .into_iter()
+        .map(|index| items[index].clone())
+        .collect();
+}
+
+#[pettymethod]
+fn reverse(this: &PtyList) {
+    this.0.lock().unwrap().reverse();
+}
+
+/// `pop([index])`, removes the last item by default.
+#[pettymethod]
+fn pop(this: &PtyList, vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    let mut vec = this.0.lock().unwrap();
+    let popped = match args.0.first() {
+        Some(index) => {
+            let index = index.downcast_ref::<PtyNum>().expect("Expected Num").0;
+            let index = PtyList::position(index, vec.len());
+            (index < vec.len()).then(|| vec.remove(index))
+        }
+        None => vec.pop(),
+    };
+    if popped.is_some() {
+        vm.shrink_list(this, 1);
+    }
+    PtyOption::new(popped)
+}
+
+#[pettymethod]
+fn insert(this: &PtyList, index: PtyNum, obj: &PettyObject, vm: &mut Vm) {
+    vm.grow_list(this, 1);
+    let mut vec = this.0.lock().unwrap();
+    let index = PtyList::position(index.0, vec.len());
+    vec.insert(index, obj.clone());
+}
+
+#[pettymethod]
+fn remove(this: &PtyList, index: PtyNum, vm: &mut Vm) -> PettyObject {
+    let mut vec = this.0.lock().unwrap();
+    let len = vec.len();
+    let position = PtyList::position(index.0, len);
+    assert!(
+        position < len,
+        "Index {index} out of range for list of length {len}"
+    );
+    vm.shrink_list(this, 1);
+    vec.remove(position)
+}
+
+#[pettymethod]
+fn extend(this: &PtyList, iterable: &PettyObject, vm: &mut Vm) {
+    let items = PtyList::collect(vm, iterable);
+    vm.grow_list(this, items.len());
+    this.0.lock().unwrap().extend(items);
+}
+
+#[pettymethod]
+fn slice(this: &PtyList, start: PtyNum, end: PtyNum, vm: &mut Vm) -> PtyList {
+    let vec = this.0.lock().unwrap();
+    let start = PtyList::position(start.0, vec.len());
+    let end = PtyList::position(end.0, vec.len()).max(start);
+    vm.new_list(vec[start..end].to_vec())
+}
+
+#[pettymethod]
+fn clear(this: &PtyList, vm: &mut Vm) {
+    let mut vec = this.0.lock().unwrap();
+    vm.shrink_list(this, vec.len());
+    vec.clear();
+}
+
+#[pettymethod]
+fn copy(this: &PtyList, vm: &mut Vm) -> PtyList {
+    vm.new_list(this.items())
+}
+
+#[pettymethod]
+fn min(this: &PtyList, vm: &mut Vm) -> PettyObject {
+    let min = this.items().into_iter().reduce(|min, item| {
+        if less_than(vm, &item, &min) {
+            item
+        } else {
+            min
+        }
+    });
+    PtyOption::new(min)
+}
+
+#[pettymethod]
+fn max(this: &PtyList, vm: &mut Vm) -> PettyObject {
+    let max = this.items().into_iter().reduce(|max, item| {
+        if less_than(vm, &max, &item) {
+            item
+        } else {
+            max
+        }
+    });
+    PtyOption::new(max)
+}
+
+/// Adds the items together with `__add__`, an empty list sums to `0`.
+#[pettymethod]
+fn sum(this: &PtyList, vm: &mut Vm) -> PettyObject {
+    this.items()
+        .into_iter()
+        .reduce(|acc, item| acc.call_method(vm, "__add__", FuncArgs(&[&acc, &item])))
+        .unwrap_or_else(|| PtyNum(0.0).into())
+}
+
+/// A list of `[index, item]` pairs.
+#[pettymethod]
+fn enumerate(this: &PtyList, vm: &mut Vm) -> PtyList {
+    #[allow(clippy::cast_precision_loss)]
+    let pairs = this
+        .items()
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| vm.new_list(vec![PtyNum(index as f64).into(), item]).into())
+        .collect();
+    vm.new_list(pairs)
+}
+
+/// A list of `[lhs, rhs]` pairs, as long as the shorter of the two.
+#[pettymethod]
+fn zip(this: &PtyList, other: &PettyObject, vm: &mut Vm) -> PtyList {
+    let other = PtyList::collect(vm, other);
+    let pairs = this
+        .items()
+        .into_iter()
+        .zip(other)
+        .map(|(lhs, rhs)| vm.new_list(vec![lhs, rhs]).into())
+        .collect();
+    vm.new_list(pairs)
+}
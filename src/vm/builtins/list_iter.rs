@@ -1,7 +1,11 @@
+use super::iterator;
 use crate::vm::prelude::*;
 
 #[derive(Clone)]
-pub struct PtyListIter(pub Arc<Mutex<Vec<PettyObject>>>, pub Arc<Mutex<usize>>);
+pub struct PtyListIter(
+    pub Arc<Counted<Mutex<Vec<PettyObject>>>>,
+    pub Arc<Mutex<usize>>,
+);
 
 impl PettyObjectType for PtyListIter {
     fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, str: &str) -> PettyObject {
@@ -10,7 +14,7 @@ impl PettyObjectType for PtyListIter {
             "__iter__" | "iter" => __ITER__.clone(),
             "__repr__" => __REPR__.clone(),
             "__len__" | "len" => __LEN__.clone(),
-            _ => todo!("{str}"),
+            _ => iterator::method(str).unwrap_or_else(|| todo!("{str}")),
         }
     }
     fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
@@ -1,4 +1,7 @@
 #![allow(clippy::needless_pass_by_value)]
+mod dict;
+mod format;
+pub mod iterator;
 mod list;
 mod list_iter;
 mod module;
@@ -8,22 +11,30 @@ mod option;
 mod print;
 mod pty_bool;
 mod range;
+mod range_iter;
 mod ref_count;
 mod repr;
+mod result;
+mod str_iter;
 mod string;
 
 use std::fmt;
 
 use self::ref_count::GETREFCOUNT;
 use super::{core::Vm, object::PettyObject, raw_function::RawFunction, stdlib};
+pub use dict::{DictMap, PtyDict};
+pub use iterator::PtyIterator;
 pub use list::PtyList;
 pub use list_iter::PtyListIter;
 pub use module::Module;
 pub use null::{PtyNull, NULL};
-pub use number::PtyNum;
+pub use number::{PtyNum, CEIL, FLOOR, ROUND, TRUNC};
 pub use option::{PtyOption, NONE};
 pub use pty_bool::{PtyBool, FALSE, TRUE};
-pub use range::RANGE;
+pub use range::{PtyRange, RANGE};
+pub use range_iter::PtyRangeIter;
+pub use result::PtyResult;
+pub use str_iter::PtyStrIter;
 pub use string::PtyStr;
 
 pub fn load_builtins(vm: &mut Vm) {
@@ -31,7 +42,11 @@ pub fn load_builtins(vm: &mut Vm) {
         ("print", RawFunction(print::print).into()),
         ("repr", RawFunction(repr::repr).into()),
         ("range", RANGE.clone()),
+        ("iter", iterator::ITER.clone()),
+        ("dict", dict::DICT.clone()),
         ("Some", RawFunction(option::some).into()),
+        ("Ok", RawFunction(result::ok).into()),
+        ("Err", RawFunction(result::err).into()),
         ("getrefcount", GETREFCOUNT.clone()),
         ("None", PtyOption(None).into()),
         ("std", stdlib::init().into()),
@@ -13,12 +13,16 @@ impl PettyObjectType for PtyNull {
         match str {
             "__bool__" => __BOOL__.clone(),
             "__repr__" => __REPR__.clone(),
+            "__is_eq__" => __IS_EQ__.clone(),
             _ => todo!(),
         }
     }
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+    fn static_items(&self) -> bool {
+        true
+    }
 }
 impl fmt::Display for PtyNull {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -35,3 +39,8 @@ fn __bool__(_self: PtyNull) -> PettyObject {
 fn __repr__(_self: PtyNull) -> PettyObject {
     NULL_STR.clone()
 }
+
+#[pettymethod]
+fn __is_eq__(_self: PtyNull, rhs: &PettyObject) -> PettyObject {
+    PtyBool::new(rhs.downcast_ref::<PtyNull>().is_some())
+}
@@ -1,3 +1,4 @@
+use crate::ast::BinOp;
 use crate::vm::prelude::*;
 
 #[derive(Clone, Copy)]
@@ -22,13 +23,45 @@ impl PettyObjectType for PtyNum {
             "__neg__" => __NEG__.clone(),
             "__repr__" => __REPR__.clone(),
             "abs" => ABS.clone(),
+            "floor" => FLOOR.clone(),
+            "ceil" => CEIL.clone(),
+            "round" => ROUND.clone(),
+            "trunc" => TRUNC.clone(),
             _ => todo!("{str}"),
         }
     }
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+    fn static_items(&self) -> bool {
+        true
+    }
+}
+impl PtyNum {
+    /// Computes `lhs op rhs` for two numbers like the methods below, without looking them up.
+    /// The interpreters' fast path for arithmetic, `None` for other operands.
+    #[allow(clippy::float_cmp)]
+    pub fn binary_op(op: BinOp, lhs: &PettyObject, rhs: &PettyObject) -> Option<PettyObject> {
+        if !crate::vm::fast_dispatch() {
+            return None;
+        }
+        let (PtyNum(lhs), PtyNum(rhs)) = (*lhs.downcast_ref()?, *rhs.downcast_ref()?);
+        Some(match op {
+            BinOp::Add => PtyNum(lhs + rhs).into(),
+            BinOp::Sub => PtyNum(lhs - rhs).into(),
+            BinOp::Mul => PtyNum(lhs * rhs).into(),
+            BinOp::Div => PtyNum(lhs / rhs).into(),
+            BinOp::Mod => PtyNum(lhs % rhs).into(),
+            BinOp::IsEq => PtyBool::new(lhs == rhs),
+            BinOp::LT => PtyBool::new(lhs < rhs),
+            BinOp::GT => PtyBool::new(lhs > rhs),
+            BinOp::LTEq => PtyBool::new(lhs <= rhs),
+            BinOp::GTEq => PtyBool::new(lhs >= rhs),
+            _ => return None,
+        })
+    }
 }
+
 impl fmt::Display for PtyNum {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -96,3 +129,25 @@ fn __repr__(lhs: PtyNum) -> PtyStr {
 fn abs(self_: PtyNum) -> PtyNum {
     PtyNum(self_.0.abs())
 }
+#[pettymethod]
+pub fn floor(self_: PtyNum) -> PtyNum {
+    PtyNum(self_.0.floor())
+}
+#[pettymethod]
+pub fn ceil(self_: PtyNum) -> PtyNum {
+    PtyNum(self_.0.ceil())
+}
+#[pettymethod]
+pub fn trunc(self_: PtyNum) -> PtyNum {
+    PtyNum(self_.0.trunc())
+}
+/// `round(num)` rounds half away from zero, `round(num, digits)` keeps `digits` decimal places.
+#[pettymethod]
+pub fn round(self_: PtyNum, args: FuncArgs) -> PtyNum {
+    let Some(digits) = args.0.first() else {
+        return PtyNum(self_.0.round());
+    };
+    let digits = digits.downcast_ref::<PtyNum>().expect("Expected Number");
+    let scale = 10f64.powf(digits.0.trunc());
+    PtyNum((self_.0 * scale).round() / scale)
+}
@@ -19,14 +19,25 @@ impl PettyObjectType for PtyOption {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+    fn static_items(&self) -> bool {
+        true
+    }
     fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
         todo!()
     }
     fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
         match key {
             "unwrap" => UNWRAP.clone(),
+            "unwrap_or" => UNWRAP_OR.clone(),
+            "unwrap_or_else" => UNWRAP_OR_ELSE.clone(),
+            "expect" => EXPECT.clone(),
             "is_some" => IS_SOME.clone(),
             "is_none" => IS_NONE.clone(),
+            "map" => MAP.clone(),
+            "and_then" => AND_THEN.clone(),
+            "or" => OR.clone(),
+            "ok_or" => OK_OR.clone(),
+            "__is_eq__" => __IS_EQ__.clone(),
             "__repr__" => __REPR__.clone(),
             _ => todo!("{key}"),
         }
@@ -65,6 +76,69 @@ fn is_none(self_: &PtyOption) -> PtyBool {
     PtyBool(self_.0.is_none())
 }
 
+#[pettymethod]
+fn unwrap_or(self_: &PtyOption, default: &PettyObject) -> PettyObject {
+    self_.0.clone().unwrap_or_else(|| default.clone())
+}
+
+/// Calls `func` when there is no value.
+#[pettymethod]
+fn unwrap_or_else(self_: &PtyOption, func: &PettyObject, vm: &mut Vm) -> PettyObject {
+    match &self_.0 {
+        Some(obj) => obj.clone(),
+        None => func.call(vm, func, FuncArgs(&[])),
+    }
+}
+
+#[pettymethod]
+fn expect(self_: &PtyOption, message: &PtyStr) -> PettyObject {
+    self_.0.clone().unwrap_or_else(|| panic!("{message}"))
+}
+
+#[pettymethod]
+fn map(self_: &PtyOption, func: &PettyObject, vm: &mut Vm) -> PettyObject {
+    PtyOption::new(
+        self_
+            .0
+            .as_ref()
+            .map(|obj| func.call(vm, func, FuncArgs(&[obj]))),
+    )
+}
+
+/// Calls `func` with the value, which should itself return an `Option`.
+#[pettymethod]
+fn and_then(self_: &PtyOption, func: &PettyObject, vm: &mut Vm) -> PettyObject {
+    match &self_.0 {
+        Some(obj) => func.call(vm, func, FuncArgs(&[obj])),
+        None => NONE.clone(),
+    }
+}
+
+#[pettymethod]
+fn or(self_: &PtyOption, other: &PettyObject) -> PettyObject {
+    match &self_.0 {
+        Some(_) => self_.clone().into(),
+        None => other.clone(),
+    }
+}
+
+#[pettymethod]
+fn ok_or(self_: &PtyOption, err: &PettyObject) -> PtyResult {
+    PtyResult(self_.0.clone().ok_or_else(|| err.clone()))
+}
+
+#[pettymethod]
+fn __is_eq__(lhs: &PtyOption, rhs: &PettyObject, vm: &mut Vm) -> PettyObject {
+    let Some(rhs) = rhs.downcast_ref::<PtyOption>() else {
+        return FALSE.clone();
+    };
+    match (&lhs.0, &rhs.0) {
+        (Some(lhs), Some(rhs)) => lhs.call_method(vm, "__is_eq__", FuncArgs(&[lhs, rhs])),
+        (None, None) => TRUE.clone(),
+        _ => FALSE.clone(),
+    }
+}
+
 #[pettymethod]
 pub fn some(obj: &PettyObject) -> PtyOption {
     PtyOption(Some(obj.clone()))
@@ -13,6 +13,7 @@ impl PettyObjectType for PtyBool {
             "__not__" => __NOT__.clone(),
             "__and__" => __AND__.clone(),
             "__or__" => __OR__.clone(),
+            "__is_eq__" => __IS_EQ__.clone(),
             "__repr__" => __REPR__.clone(),
             _ => todo!("{str}"),
         }
@@ -23,6 +24,9 @@ impl PettyObjectType for PtyBool {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+    fn static_items(&self) -> bool {
+        true
+    }
 }
 
 impl PtyBool {
@@ -35,6 +39,14 @@ impl PtyBool {
             false => FALSE.clone(),
         }
     }
+    /// Whether `object` is truthy by its `__bool__`, which bools skip.
+    pub fn truthy(vm: &mut Vm, object: &PettyObject) -> bool {
+        if let Some(bool) = object.downcast_ref::<PtyBool>() {
+            return bool.0;
+        }
+        let bool = object.call_method(vm, "__bool__", FuncArgs(&[]));
+        bool.downcast_ref::<PtyBool>().expect("Expected Bool").0
+    }
 }
 
 impl fmt::Display for PtyBool {
@@ -63,6 +75,11 @@ fn __and__(lhs: PtyBool, rhs: PtyBool) -> PtyBool {
 fn __or__(lhs: PtyBool, rhs: PtyBool) -> PtyBool {
     PtyBool(lhs.0 || rhs.0)
 }
+#[pettymethod]
+fn __is_eq__(lhs: PtyBool, rhs: &PettyObject) -> PettyObject {
+    let rhs = rhs.downcast_ref::<PtyBool>();
+    PtyBool::new(rhs.is_some_and(|rhs| rhs.0 == lhs.0))
+}
 #[allow(clippy::match_bool)]
 #[pettymethod]
 fn __repr__(self_: PtyBool) -> PtyStr {
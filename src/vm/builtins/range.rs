@@ -1,22 +1,44 @@
+use super::iterator;
+use super::range_iter::PtyRangeIter;
 use crate::vm::prelude::*;
 
-#[derive(Clone)]
+/// `range(end)`, `range(start, end)` or `range(start, end, step)`.
+/// The range itself is immutable, iterating it with `__iter__` creates a fresh iterator.
+#[derive(Clone, Copy)]
 pub struct PtyRange {
+    start: f64,
     end: f64,
     step: f64,
-    current: Arc<Mutex<f64>>,
 }
 
 impl PtyRange {
-    #[inline]
-    pub fn next(&self) -> Option<f64> {
-        let mut current = self.current.lock().unwrap();
-        let prev_current = *current;
-        *current += self.step;
-        if *current > self.end {
-            return None;
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    pub fn len(&self) -> usize {
+        ((self.end - self.start) / self.step).ceil().max(0.0) as usize
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The value at `index`, computed from `start` so errors don't accumulate.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn nth(&self, index: usize) -> Option<f64> {
+        (index < self.len()).then_some(self.start + index as f64 * self.step)
+    }
+    fn contains(&self, num: f64) -> bool {
+        let index = (num - self.start) / self.step;
+        #[allow(clippy::cast_precision_loss)]
+        let in_bounds = index >= 0.0 && index < self.len() as f64;
+        in_bounds && index.fract() == 0.0
+    }
+    fn reversed(&self) -> Self {
+        match self.nth(self.len().saturating_sub(1)) {
+            Some(last) => Self {
+                start: last,
+                end: self.start - self.step,
+                step: -self.step,
+            },
+            None => *self,
         }
-        Some(prev_current)
     }
 }
 
@@ -24,13 +46,12 @@ impl PettyObjectType for PtyRange {
     fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, str: &str) -> PettyObject {
         match str {
             "__iter__" | "iter" => __ITER__.clone(),
-            "__next__" | "next" => __NEXT__.clone(),
             "__len__" | "len" => __LEN__.clone(),
             "__repr__" => __REPR__.clone(),
-            "sum" => SUM.clone(),
-            "product" => PRODUCT.clone(),
-
-            _ => todo!("{str}"),
+            "get" | "__get_index__" => GET.clone(),
+            "contains" => CONTAINS.clone(),
+            "reversed" => REVERSED.clone(),
+            _ => iterator::method(str).unwrap_or_else(|| todo!("{str}")),
         }
     }
     fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
@@ -39,60 +60,80 @@ impl PettyObjectType for PtyRange {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+    fn static_items(&self) -> bool {
+        true
+    }
 }
 
 impl fmt::Display for PtyRange {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "range at {:?}", self as *const Self)
+        let Self { start, end, step } = self;
+        if *step == 1.0 {
+            write!(f, "range({start}, {end})")
+        } else {
+            write!(f, "range({start}, {end}, {step})")
+        }
     }
 }
 
 #[pettymethod]
-pub fn range(end: PtyNum) -> PtyRange {
-    PtyRange {
-        end: end.0,
-        current: Mutex::new(0.0).into(),
-        step: 1.0,
-    }
+pub fn range(args: FuncArgs) -> PtyRange {
+    let nums: Vec<f64> = args
+        .0
+        .iter()
+        .map(|arg| arg.downcast_ref::<PtyNum>().expect("Expected Num").0)
+        .collect();
+    let (start, end, step) = match nums[..] {
+        [end] => (0.0, end, 1.0),
+        [start, end] => (start, end, 1.0),
+        [start, end, step] => (start, end, step),
+        _ => panic!("range expects 1 to 3 arguments, got {}", nums.len()),
+    };
+    assert!(step != 0.0, "range step must not be zero");
+    PtyRange { start, end, step }
 }
 
 #[pettymethod]
-fn __iter__(this: PtyRange) -> PtyRange {
-    this
+fn __iter__(this: PtyRange) -> PtyRangeIter {
+    PtyRangeIter::new(this)
 }
 
 #[pettymethod]
-fn __next__(this: &PtyRange) -> PettyObject {
-    match this.next() {
-        Some(num) => PtyOption(Some(PtyNum(num).into())).into(),
-        None => NONE.clone(),
-    }
+#[allow(clippy::cast_precision_loss)]
+fn __len__(this: PtyRange) -> PtyNum {
+    PtyNum(this.len() as f64)
 }
 
 #[pettymethod]
-fn __len__(_this: PtyRange) -> PtyNum {
-    todo!()
+fn __repr__(this: PtyRange) -> PtyStr {
+    PtyStr(format!("{this}").into())
 }
 
+/// Indexes into the range, negative indices count from the end.
 #[pettymethod]
-fn __repr__(this: PtyRange) -> PtyStr {
-    PtyStr(format!("{this}").into())
+fn get(this: PtyRange, index: PtyNum) -> PettyObject {
+    #[allow(clippy::cast_precision_loss)]
+    let index = if index.0 < 0.0 {
+        index.0 + this.len() as f64
+    } else {
+        index.0
+    };
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let nth = (index >= 0.0 && index.fract() == 0.0)
+        .then(|| this.nth(index as usize))
+        .flatten();
+    PtyOption::new(nth.map(|num| PtyNum(num).into()))
 }
 
 #[pettymethod]
-fn sum(this: &PtyRange) -> PtyNum {
-    let mut sum = 0.0;
-    while let Some(next) = this.next() {
-        sum += next;
-    }
-    PtyNum(sum)
+fn contains(this: PtyRange, obj: &PettyObject) -> PettyObject {
+    let contains = obj
+        .downcast_ref::<PtyNum>()
+        .is_some_and(|num| this.contains(num.0));
+    PtyBool::new(contains)
 }
 
 #[pettymethod]
-fn product(this: &PtyRange) -> PtyNum {
-    let mut sum = 0.0;
-    while let Some(next) = this.next() {
-        sum *= next;
-    }
-    PtyNum(sum)
+fn reversed(this: PtyRange) -> PtyRange {
+    this.reversed()
 }
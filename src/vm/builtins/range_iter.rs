@@ -0,0 +1,62 @@
+use super::iterator;
+use super::range::PtyRange;
+use crate::vm::prelude::*;
+
+/// Iterates over a [`PtyRange`], keeping the index of the next value.
+#[derive(Clone)]
+pub struct PtyRangeIter(PtyRange, Arc<Mutex<usize>>);
+
+impl PtyRangeIter {
+    pub fn new(range: PtyRange) -> Self {
+        Self(range, Mutex::new(0).into())
+    }
+}
+
+impl PettyObjectType for PtyRangeIter {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, str: &str) -> PettyObject {
+        match str {
+            "__next__" | "next" => __NEXT__.clone(),
+            "__iter__" | "iter" => __ITER__.clone(),
+            "__repr__" => __REPR__.clone(),
+            "__len__" | "len" => __LEN__.clone(),
+            _ => iterator::method(str).unwrap_or_else(|| panic!("range iterator has no attribute {str}")),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("range iterator is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for PtyRangeIter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "iterator at {:?}", self as *const Self)
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &PtyRangeIter) -> PtyStr {
+    PtyStr(format!("{this}").into())
+}
+
+#[pettymethod]
+fn __next__(this: &PtyRangeIter) -> PettyObject {
+    let mut index = this.1.lock().unwrap();
+    let next = this.0.nth(*index);
+    *index += 1;
+    PtyOption::new(next.map(|num| PtyNum(num).into()))
+}
+
+#[pettymethod]
+fn __iter__(this: PtyRangeIter) -> PtyRangeIter {
+    this
+}
+
+#[pettymethod]
+fn __len__(this: &PtyRangeIter) -> PtyNum {
+    let consumed = *this.1.lock().unwrap();
+    #[allow(clippy::cast_precision_loss)]
+    PtyNum(this.0.len().saturating_sub(consumed) as f64)
+}
@@ -0,0 +1,172 @@
+use crate::vm::prelude::*;
+
+/// `Ok(value)` or `Err(error)`, created with the `Ok` and `Err` builtins.
+#[derive(Clone)]
+pub struct PtyResult(pub Result<PettyObject, PettyObject>);
+
+impl PettyObjectType for PtyResult {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn static_items(&self) -> bool {
+        true
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("result is not callable")
+    }
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "is_ok" => IS_OK.clone(),
+            "is_err" => IS_ERR.clone(),
+            "ok" => OK_VALUE.clone(),
+            "err" => ERR_VALUE.clone(),
+            "unwrap" => UNWRAP.clone(),
+            "unwrap_err" => UNWRAP_ERR.clone(),
+            "unwrap_or" => UNWRAP_OR.clone(),
+            "unwrap_or_else" => UNWRAP_OR_ELSE.clone(),
+            "expect" => EXPECT.clone(),
+            "map" => MAP.clone(),
+            "map_err" => MAP_ERR.clone(),
+            "and_then" => AND_THEN.clone(),
+            "or" => OR.clone(),
+            "__is_eq__" => __IS_EQ__.clone(),
+            "__repr__" => __REPR__.clone(),
+            _ => panic!("result has no attribute {key}"),
+        }
+    }
+}
+
+impl fmt::Display for PtyResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Ok(obj) => write!(f, "Ok({obj})"),
+            Err(obj) => write!(f, "Err({obj})"),
+        }
+    }
+}
+
+#[pettymethod]
+pub fn ok(obj: &PettyObject) -> PtyResult {
+    PtyResult(Ok(obj.clone()))
+}
+
+#[pettymethod]
+pub fn err(obj: &PettyObject) -> PtyResult {
+    PtyResult(Err(obj.clone()))
+}
+
+#[pettymethod]
+fn is_ok(self_: &PtyResult) -> PtyBool {
+    PtyBool(self_.0.is_ok())
+}
+
+#[pettymethod]
+fn is_err(self_: &PtyResult) -> PtyBool {
+    PtyBool(self_.0.is_err())
+}
+
+#[pettymethod]
+fn ok_value(self_: &PtyResult) -> PettyObject {
+    PtyOption::new(self_.0.clone().ok())
+}
+
+#[pettymethod]
+fn err_value(self_: &PtyResult) -> PettyObject {
+    PtyOption::new(self_.0.clone().err())
+}
+
+#[pettymethod]
+fn unwrap(self_: &PtyResult, vm: &mut Vm) -> PettyObject {
+    match &self_.0 {
+        Ok(obj) => obj.clone(),
+        Err(err) => panic!("Called unwrap on an Err value: {}", err.force_repr(vm)),
+    }
+}
+
+#[pettymethod]
+fn unwrap_err(self_: &PtyResult, vm: &mut Vm) -> PettyObject {
+    match &self_.0 {
+        Ok(obj) => panic!("Called unwrap_err on an Ok value: {}", obj.force_repr(vm)),
+        Err(err) => err.clone(),
+    }
+}
+
+#[pettymethod]
+fn unwrap_or(self_: &PtyResult, default: &PettyObject) -> PettyObject {
+    match &self_.0 {
+        Ok(obj) => obj.clone(),
+        Err(_) => default.clone(),
+    }
+}
+
+/// Calls `func` with the error when there is no value.
+#[pettymethod]
+fn unwrap_or_else(self_: &PtyResult, func: &PettyObject, vm: &mut Vm) -> PettyObject {
+    match &self_.0 {
+        Ok(obj) => obj.clone(),
+        Err(err) => func.call(vm, func, FuncArgs(&[err])),
+    }
+}
+
+#[pettymethod]
+fn expect(self_: &PtyResult, message: &PtyStr, vm: &mut Vm) -> PettyObject {
+    match &self_.0 {
+        Ok(obj) => obj.clone(),
+        Err(err) => panic!("{message}: {}", err.force_repr(vm)),
+    }
+}
+
+#[pettymethod]
+fn map(self_: &PtyResult, func: &PettyObject, vm: &mut Vm) -> PtyResult {
+    match &self_.0 {
+        Ok(obj) => PtyResult(Ok(func.call(vm, func, FuncArgs(&[obj])))),
+        Err(_) => self_.clone(),
+    }
+}
+
+#[pettymethod]
+fn map_err(self_: &PtyResult, func: &PettyObject, vm: &mut Vm) -> PtyResult {
+    match &self_.0 {
+        Ok(_) => self_.clone(),
+        Err(err) => PtyResult(Err(func.call(vm, func, FuncArgs(&[err])))),
+    }
+}
+
+/// Calls `func` with the value, which should itself return a `Result`.
+#[pettymethod]
+fn and_then(self_: &PtyResult, func: &PettyObject, vm: &mut Vm) -> PettyObject {
+    match &self_.0 {
+        Ok(obj) => func.call(vm, func, FuncArgs(&[obj])),
+        Err(_) => self_.clone().into(),
+    }
+}
+
+#[pettymethod]
+fn or(self_: &PtyResult, other: &PettyObject) -> PettyObject {
+    match &self_.0 {
+        Ok(_) => self_.clone().into(),
+        Err(_) => other.clone(),
+    }
+}
+
+#[pettymethod]
+fn __is_eq__(lhs: &PtyResult, rhs: &PettyObject, vm: &mut Vm) -> PettyObject {
+    let Some(rhs) = rhs.downcast_ref::<PtyResult>() else {
+        return FALSE.clone();
+    };
+    match (&lhs.0, &rhs.0) {
+        (Ok(lhs), Ok(rhs)) | (Err(lhs), Err(rhs)) => {
+            lhs.call_method(vm, "__is_eq__", FuncArgs(&[lhs, rhs]))
+        }
+        _ => FALSE.clone(),
+    }
+}
+
+#[pettymethod]
+fn __repr__(self_: &PtyResult, vm: &mut Vm) -> PtyStr {
+    let repr = match &self_.0 {
+        Ok(obj) => format!("Ok({})", obj.force_repr(vm)),
+        Err(err) => format!("Err({})", err.force_repr(vm)),
+    };
+    PtyStr(repr.into())
+}
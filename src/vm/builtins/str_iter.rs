@@ -0,0 +1,64 @@
+use super::iterator;
+use crate::vm::prelude::*;
+
+/// Iterates over the characters of a string, keeping the byte offset of the next one.
+#[derive(Clone)]
+pub struct PtyStrIter(pub Arc<str>, pub Arc<Mutex<usize>>);
+
+impl PtyStrIter {
+    pub fn new(string: Arc<str>) -> Self {
+        Self(string, Mutex::new(0).into())
+    }
+}
+
+impl PettyObjectType for PtyStrIter {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, str: &str) -> PettyObject {
+        match str {
+            "__next__" | "next" => __NEXT__.clone(),
+            "__iter__" | "iter" => __ITER__.clone(),
+            "__repr__" => __REPR__.clone(),
+            "__len__" | "len" => __LEN__.clone(),
+            _ => iterator::method(str).unwrap_or_else(|| panic!("string iterator has no attribute {str}")),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("string iterator is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for PtyStrIter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "iterator at {:?}", self as *const Self)
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &PtyStrIter) -> PtyStr {
+    PtyStr(format!("{this}").into())
+}
+
+#[pettymethod]
+fn __next__(this: &PtyStrIter) -> PettyObject {
+    let mut offset = this.1.lock().unwrap();
+    let next = this.0[*offset..].chars().next();
+    let next = next.map(|char| {
+        *offset += char.len_utf8();
+        PtyStr(char.to_string().into()).into()
+    });
+    PtyOption::new(next)
+}
+
+#[pettymethod]
+fn __iter__(this: PtyStrIter) -> PtyStrIter {
+    this
+}
+
+#[pettymethod]
+fn __len__(this: &PtyStrIter) -> PtyNum {
+    let offset = this.1.lock().unwrap();
+    #[allow(clippy::cast_precision_loss)]
+    PtyNum(this.0[*offset..].chars().count() as f64)
+}
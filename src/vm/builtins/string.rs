@@ -13,6 +13,34 @@ impl PettyObjectType for PtyStr {
             "__add__" => __ADD__.clone(),
             "__mul__" => __MUL__.clone(),
             "__is_eq__" => __IS_EQ__.clone(),
+            "__lt__" => __LT__.clone(),
+            "__gt__" => __GT__.clone(),
+            "__lt_eq__" => __LT_EQ__.clone(),
+            "__gt_eq__" => __GT_EQ__.clone(),
+            "__bool__" => __BOOL__.clone(),
+            "__iter__" | "iter" | "chars" => __ITER__.clone(),
+            "__get_index__" => __GET_INDEX__.clone(),
+            "len" | "__len__" => LEN.clone(),
+            "is_empty" => IS_EMPTY.clone(),
+            "get" => GET.clone(),
+            "slice" => SLICE.clone(),
+            "contains" => CONTAINS.clone(),
+            "starts_with" => STARTS_WITH.clone(),
+            "ends_with" => ENDS_WITH.clone(),
+            "strip_prefix" => STRIP_PREFIX.clone(),
+            "strip_suffix" => STRIP_SUFFIX.clone(),
+            "rfind" => RFIND.clone(),
+            "count" => COUNT.clone(),
+            "split" => SPLIT.clone(),
+            "split_whitespace" => SPLIT_WHITESPACE.clone(),
+            "lines" => LINES.clone(),
+            "replace" => REPLACE.clone(),
+            "join" => JOIN.clone(),
+            "repeat" => __MUL__.clone(),
+            "pad_left" => PAD_LEFT.clone(),
+            "pad_right" => PAD_RIGHT.clone(),
+            "parse_int" => PARSE_INT.clone(),
+            "parse_float" => PARSE_FLOAT.clone(),
             "upper" => UPPER.clone(),
             "lower" => LOWER.clone(),
             "find" => FIND.clone(),
@@ -28,6 +56,9 @@ impl PettyObjectType for PtyStr {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+    fn static_items(&self) -> bool {
+        true
+    }
 }
 
 impl fmt::Display for PtyStr {
@@ -42,14 +73,15 @@ fn __repr__(self_: PtyStr) -> PtyStr {
 }
 
 #[pettymethod]
-fn __add__(lhs: &PtyStr, rhs: &PtyStr) -> PtyStr {
-    PtyStr((lhs.0.to_string() + &rhs.0).into())
+fn __add__(lhs: &PtyStr, rhs: &PtyStr, vm: &mut Vm) -> PettyObject {
+    vm.build_str(lhs.0.len() + rhs.0.len(), || lhs.0.to_string() + &rhs.0)
 }
 
 #[pettymethod]
-fn __mul__(lhs: &PtyStr, rhs: PtyNum) -> PtyStr {
+fn __mul__(lhs: &PtyStr, rhs: PtyNum, vm: &mut Vm) -> PettyObject {
     #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-    PtyStr(lhs.0.repeat(rhs.0.max(0.0) as usize).into())
+    let count = rhs.0.max(0.0) as usize;
+    vm.build_str(lhs.0.len().saturating_mul(count), || lhs.0.repeat(count))
 }
 
 #[pettymethod]
@@ -78,21 +110,185 @@ fn __gt__(lhs: &PtyStr, rhs: &PtyStr) -> PettyObject {
 }
 
 #[pettymethod]
-fn upper(this: &PtyStr) -> PtyStr {
-    PtyStr(this.0.to_uppercase().into())
+fn upper(this: &PtyStr, vm: &mut Vm) -> PettyObject {
+    vm.new_str(this.0.to_uppercase())
+}
+
+#[pettymethod]
+fn lower(this: &PtyStr, vm: &mut Vm) -> PettyObject {
+    vm.new_str(this.0.to_lowercase())
+}
+
+#[pettymethod]
+fn __bool__(this: &PtyStr) -> PettyObject {
+    PtyBool::new(!this.0.is_empty())
+}
+
+#[pettymethod]
+fn __iter__(this: &PtyStr) -> PtyStrIter {
+    PtyStrIter::new(this.0.clone())
+}
+
+#[pettymethod]
+fn __get_index__(this: &PtyStr, index: PtyNum) -> PtyStr {
+    let char = this.char_at(index.0);
+    let char = char.unwrap_or_else(|| panic!("Index {index} out of range for {this:?}"));
+    PtyStr(char.to_string().into())
+}
+
+#[pettymethod]
+fn len(this: &PtyStr) -> PtyNum {
+    #[allow(clippy::cast_precision_loss)]
+    PtyNum(this.0.chars().count() as f64)
+}
+
+#[pettymethod]
+fn is_empty(this: &PtyStr) -> PettyObject {
+    PtyBool::new(this.0.is_empty())
+}
+
+#[pettymethod]
+fn get(this: &PtyStr, index: PtyNum) -> Option<PettyObject> {
+    let char = this.char_at(index.0)?;
+    Some(PtyStr(char.to_string().into()).into())
+}
+
+/// The characters from `start` up to, but not including, `end`.
+/// Negative positions count from the end of the string.
+#[pettymethod]
+fn slice(this: &PtyStr, start: PtyNum, end: PtyNum) -> PtyStr {
+    let len = this.0.chars().count();
+    let start = PtyStr::char_position(start.0, len);
+    let end = PtyStr::char_position(end.0, len).max(start);
+    PtyStr(
+        this.0
+            .chars()
+            .skip(start)
+            .take(end - start)
+            .collect::<String>()
+            .into(),
+    )
+}
+
+#[pettymethod]
+fn contains(this: &PtyStr, substr: &PtyStr) -> PettyObject {
+    PtyBool::new(this.0.contains(&*substr.0))
+}
+
+#[pettymethod]
+fn starts_with(this: &PtyStr, prefix: &PtyStr) -> PettyObject {
+    PtyBool::new(this.0.starts_with(&*prefix.0))
+}
+
+#[pettymethod]
+fn ends_with(this: &PtyStr, suffix: &PtyStr) -> PettyObject {
+    PtyBool::new(this.0.ends_with(&*suffix.0))
+}
+
+#[pettymethod]
+fn strip_prefix(this: &PtyStr, prefix: &PtyStr) -> Option<PettyObject> {
+    let stripped = this.0.strip_prefix(&*prefix.0)?;
+    Some(PtyStr(stripped.into()).into())
 }
 
 #[pettymethod]
-fn lower(this: &PtyStr) -> PtyStr {
-    PtyStr(this.0.to_lowercase().into())
+fn strip_suffix(this: &PtyStr, suffix: &PtyStr) -> Option<PettyObject> {
+    let stripped = this.0.strip_suffix(&*suffix.0)?;
+    Some(PtyStr(stripped.into()).into())
 }
 
+/// The character index of the first occurrence of `substr`.
 #[pettymethod]
 fn find(this: &PtyStr, substr: &PtyStr) -> Option<PettyObject> {
+    let index = this.0.find(&*substr.0)?;
+    Some(this.char_index(index).into())
+}
+
+/// The character index of the last occurrence of `substr`.
+#[pettymethod]
+fn rfind(this: &PtyStr, substr: &PtyStr) -> Option<PettyObject> {
+    let index = this.0.rfind(&*substr.0)?;
+    Some(this.char_index(index).into())
+}
+
+#[pettymethod]
+fn count(this: &PtyStr, substr: &PtyStr) -> PtyNum {
     #[allow(clippy::cast_precision_loss)]
-    this.0
-        .find(substr.0.as_ref())
-        .map(|i| PtyNum(i as f64).into())
+    PtyNum(this.0.matches(&*substr.0).count() as f64)
+}
+
+/// `split()` splits on whitespace, `split(sep)` on every occurrence of `sep`.
+#[pettymethod]
+fn split(this: &PtyStr, vm: &mut Vm, args: FuncArgs) -> PtyList {
+    let Some(sep) = args.0.first() else {
+        return str_list(vm, this.0.split_whitespace());
+    };
+    let sep = sep.downcast_ref::<PtyStr>().expect("Expected Str");
+    assert!(!sep.0.is_empty(), "Cannot split on an empty separator");
+    str_list(vm, this.0.split(&*sep.0))
+}
+
+#[pettymethod]
+fn split_whitespace(this: &PtyStr, vm: &mut Vm) -> PtyList {
+    str_list(vm, this.0.split_whitespace())
+}
+
+#[pettymethod]
+fn lines(this: &PtyStr, vm: &mut Vm) -> PtyList {
+    str_list(vm, this.0.lines())
+}
+
+#[pettymethod]
+fn replace(this: &PtyStr, from: &PtyStr, to: &PtyStr, vm: &mut Vm) -> PettyObject {
+    let count = this.0.matches(&*from.0).count();
+    let len =
+        (this.0.len() - count * from.0.len()).saturating_add(count.saturating_mul(to.0.len()));
+    vm.build_str(len, || this.0.replace(&*from.0, &to.0))
+}
+
+/// Joins the `repr` of every item in `list`, with `this` between each of them.
+#[pettymethod]
+fn join(this: &PtyStr, list: &PtyList, vm: &mut Vm) -> PettyObject {
+    let items = list.0.lock().unwrap().clone();
+    let items: Vec<_> = items.iter().map(|item| item.force_repr(vm).0).collect();
+    let separators = this.0.len().saturating_mul(items.len().saturating_sub(1));
+    let len = items.iter().map(|item| item.len()).sum::<usize>() + separators;
+    vm.build_str(len, || items.join(&this.0))
+}
+
+#[pettymethod]
+fn pad_left(this: &PtyStr, width: PtyNum, vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    let padding = this.padding(vm, width, &args);
+    vm.new_str(padding + &this.0)
+}
+
+#[pettymethod]
+fn pad_right(this: &PtyStr, width: PtyNum, vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    let padding = this.padding(vm, width, &args);
+    vm.new_str(this.0.to_string() + &padding)
+}
+
+/// `parse_int()` parses base 10, `parse_int(radix)` any base from 2 to 36.
+#[pettymethod]
+fn parse_int(this: &PtyStr, args: FuncArgs) -> Option<PettyObject> {
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let radix = match args.0.first() {
+        Some(radix) => radix.downcast_ref::<PtyNum>().expect("Expected Number").0 as u32,
+        None => 10,
+    };
+    assert!(
+        (2..=36).contains(&radix),
+        "parse_int: radix must be from 2 to 36"
+    );
+    let int = i128::from_str_radix(this.0.trim(), radix).ok()?;
+    #[allow(clippy::cast_precision_loss)]
+    Some(PtyNum(int as f64).into())
+}
+
+#[pettymethod]
+fn parse_float(this: &PtyStr) -> Option<PettyObject> {
+    let float = this.0.trim().parse().ok()?;
+    Some(PtyNum(float).into())
 }
 
 #[pettymethod]
@@ -120,24 +316,74 @@ fn trim_end_matches(this: &PtyStr, pat: &PtyStr) -> PtyStr {
     PtyStr(this.0.trim_end_matches(pat.0.as_ref()).into())
 }
 
-// TODO - use #[pettymethod]
-pub static STR_FORMAT: Lazy<PettyObject> = Lazy::new(|| RawFunction(str_format).into());
-fn str_format(vm: &mut Vm, _this: &PettyObject, args: FuncArgs) -> PettyObject {
-    let mut args = args.0.iter();
-    let first_arg = args.next().unwrap();
-    let Some(PtyStr(format_str)) = first_arg.downcast_ref::<PtyStr>() else {
-        println!("{first_arg}");
-        todo!();
-    };
-    let mut output = String::new();
-    for (index, seg) in format_str.split("{}").enumerate() {
-        if index != 0 {
-            let repr = args.next().unwrap().repr(vm).unwrap();
-            output.push_str(&repr.0);
+/// See [`super::format`] for the placeholder syntax.
+#[pettymethod]
+fn str_format(this: &PtyStr, vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    let string = super::format::format(vm, &this.0, args.0);
+    vm.new_str(string)
+}
+
+impl PtyStr {
+    /// Resolves a possibly negative character position, clamped to `0..=len`.
+    fn char_position(position: f64, len: usize) -> usize {
+        #[allow(clippy::cast_precision_loss)]
+        let position = if position < 0.0 {
+            position + len as f64
+        } else {
+            position
+        };
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let position = position.max(0.0) as usize;
+        position.min(len)
+    }
+    fn char_at(&self, index: f64) -> Option<char> {
+        #[allow(clippy::cast_precision_loss)]
+        let index = if index < 0.0 {
+            index + self.0.chars().count() as f64
+        } else {
+            index
+        };
+        if index < 0.0 {
+            return None;
         }
-        output.push_str(seg);
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        self.0.chars().nth(index as usize)
+    }
+    fn char_index(&self, byte_index: usize) -> PtyNum {
+        #[allow(clippy::cast_precision_loss)]
+        PtyNum(self.0[..byte_index].chars().count() as f64)
+    }
+    fn padding(&self, vm: &Vm, width: PtyNum, args: &FuncArgs) -> String {
+        let fill = match args.0.first() {
+            Some(fill) => fill
+                .downcast_ref::<PtyStr>()
+                .expect("Expected Str")
+                .0
+                .clone(),
+            None => " ".into(),
+        };
+        assert_eq!(
+            fill.chars().count(),
+            1,
+            "Padding must be a single character"
+        );
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let width = width.0.max(0.0) as usize;
+        let count = width.saturating_sub(self.0.chars().count());
+        vm.check_memory((fill.len() * count).saturating_add(self.0.len()));
+        fill.repeat(count)
+    }
+}
+
+fn str_list<'a>(vm: &Vm, parts: impl Iterator<Item = &'a str>) -> PtyList {
+    let parts = parts.map(|part| vm.new_str(part)).collect();
+    vm.new_list(parts)
+}
+
+impl fmt::Debug for PtyStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
     }
-    PtyStr(output.into()).into()
 }
 
 impl From<String> for PtyStr {
@@ -0,0 +1,30 @@
+use std::any::TypeId;
+use std::sync::OnceLock;
+
+use crate::vm::prelude::*;
+
+/// Remembers the item a call site looks up, keyed on the type of the first receiver
+/// with [`static_items`](PettyObjectType::static_items).
+/// Receivers of any other type take the slow path.
+#[derive(Default)]
+pub struct InlineCache(OnceLock<(TypeId, PettyObject)>);
+
+impl InlineCache {
+    pub fn get_item(&self, vm: &mut Vm, object: &PettyObject, key: &str) -> PettyObject {
+        if !crate::vm::fast_dispatch() {
+            return object.get_item(vm, object, key);
+        }
+        let type_id = object.as_any().type_id();
+        if let Some((cached, item)) = self.0.get() {
+            if *cached == type_id {
+                return item.clone();
+            }
+        }
+        let item = object.get_item(vm, object, key);
+        if object.static_items() {
+            // Another thread running the same code may have filled it first.
+            let _ = self.0.set((type_id, item.clone()));
+        }
+        item
+    }
+}
@@ -0,0 +1,361 @@
+use std::sync::Arc;
+
+use super::cache::InlineCache;
+use super::instruction::{ClassCode, Code, FunctionCode, Index, Instruction};
+use super::resolver::{ResolveError, Resolved, Resolver};
+use crate::ast::{BinOp, Literal, Node};
+use crate::vm::prelude::*;
+
+/// Compiles [`Node`]s into [`Code`], evaluating in the same order as the tree walker.
+pub struct Compiler<'r> {
+    code: Code,
+    /// The pending `break` jumps of each loop we're inside, innermost last.
+    loops: Vec<Loop>,
+    resolver: &'r mut Resolver,
+    /// Whether a `return` can reuse the frame, not at the top level or in generators
+    /// where calling the function again makes a new generator.
+    tail_calls: bool,
+}
+
+struct Loop {
+    breaks: Vec<usize>,
+    /// `for` loops keep their iterator on the stack, which `break` has to pop.
+    has_iterator: bool,
+}
+
+impl<'r> Compiler<'r> {
+    fn new(resolver: &'r mut Resolver) -> Self {
+        Self {
+            code: Code::default(),
+            loops: Vec::new(),
+            resolver,
+            tail_calls: false,
+        }
+    }
+
+    /// Compiles each top level statement into code returning its value, like `Vm::evaluate_list`.
+    /// `globals` are the names defined before running, any other name that's never assigned
+    /// is reported before anything runs.
+    pub fn compile(
+        globals: impl IntoIterator<Item = Arc<str>>,
+        nodes: &[Node],
+    ) -> Result<Vec<Code>, Vec<ResolveError>> {
+        let mut resolver = Resolver::new(globals, nodes);
+        let codes = nodes
+            .iter()
+            .map(|node| {
+                let mut compiler = Compiler::new(&mut resolver);
+                compiler.expr(node);
+                compiler.emit(Instruction::Return);
+                compiler.code
+            })
+            .collect();
+        if resolver.errors.is_empty() {
+            Ok(codes)
+        } else {
+            Err(resolver.errors)
+        }
+    }
+
+    fn function(&mut self, name: Arc<str>, params: &[Arc<str>], body: &[Node]) -> FunctionCode {
+        self.resolver.enter_function(params, body);
+        let generator = Node::contains_yield(body);
+        let mut compiler = Compiler::new(self.resolver);
+        compiler.tail_calls = !generator;
+        compiler.block(body);
+        let null = compiler.constant(NULL.clone());
+        compiler.emit(Instruction::Constant(null));
+        compiler.emit(Instruction::Return);
+        let code = compiler.code;
+        let scope = self.resolver.exit_function();
+        FunctionCode {
+            name,
+            params: params
+                .iter()
+                .map(|param| index(scope.locals.get_index_of(param).unwrap()))
+                .collect(),
+            code,
+            generator,
+            locals: scope.locals.into_iter().collect(),
+            fallbacks: scope.fallbacks,
+            cells: scope.cells,
+            captures: scope
+                .captures
+                .into_iter()
+                .zip(scope.capture_sources)
+                .collect(),
+        }
+    }
+
+    fn statement(&mut self, node: &Node) {
+        match node {
+            Node::Globals(nodes) | Node::Block(nodes) => {
+                for node in nodes.iter() {
+                    self.statement(node);
+                }
+            }
+            Node::SetEq(name, expr) => {
+                self.expr(expr);
+                self.store(name);
+            }
+            Node::FuncDef(name, params, body) => {
+                let function = self.function(name.clone(), params, body);
+                let index = index(self.code.functions.len());
+                self.code.functions.push(Arc::new(function));
+                self.emit(Instruction::MakeFunction(index));
+                self.store(name);
+            }
+            Node::ClassDef(name, fields, methods) => {
+                let methods = methods
+                    .iter()
+                    .map(|method| {
+                        let Node::FuncDef(name, params, body) = method else {
+                            unreachable!();
+                        };
+                        Arc::new(self.function(name.clone(), params, body))
+                    })
+                    .collect();
+                let index = index(self.code.classes.len());
+                self.code.classes.push(ClassCode {
+                    fields: fields.clone(),
+                    methods,
+                });
+                self.emit(Instruction::MakeClass(index));
+                self.store(name);
+            }
+            Node::ReturnState(expr) => match &**expr {
+                Node::FuncCall(name, args) if self.tail_calls => {
+                    self.load(name);
+                    self.exprs(args);
+                    self.emit(Instruction::TailCall(index(args.len())));
+                }
+                expr => {
+                    self.expr(expr);
+                    self.emit(Instruction::Return);
+                }
+            },
+            Node::IfState(condition, block, or_else) => {
+                self.expr(condition);
+                let jump_to_else = self.emit(Instruction::JumpIfFalse(0));
+                self.block(block);
+                let jump_to_end = self.emit(Instruction::Jump(0));
+                self.patch(jump_to_else);
+                if let Some(or_else) = or_else {
+                    self.statement(or_else);
+                }
+                self.patch(jump_to_end);
+            }
+            Node::WhileLoop(condition, block) => {
+                let start = self.code.instructions.len();
+                self.expr(condition);
+                let jump_to_end = self.emit(Instruction::JumpIfFalse(0));
+                self.loop_body(block, false);
+                self.emit(Instruction::Jump(index(start)));
+                self.patch(jump_to_end);
+                self.patch_breaks();
+            }
+            Node::ForLoop(target, iter, block) => {
+                self.expr(iter);
+                self.emit(Instruction::GetIter);
+                let start = self.code.instructions.len();
+                let cache = self.cache();
+                let for_iter = self.emit(Instruction::ForIter(0, cache));
+                self.store(target);
+                self.loop_body(block, true);
+                self.emit(Instruction::Jump(index(start)));
+                self.patch(for_iter);
+                self.patch_breaks();
+            }
+            Node::BreakState => {
+                let has_iterator = match self.loops.last() {
+                    Some(current) => current.has_iterator,
+                    None => panic!("break can only be used inside a loop"),
+                };
+                if has_iterator {
+                    self.emit(Instruction::Pop);
+                }
+                let jump = self.emit(Instruction::Jump(0));
+                self.loops.last_mut().unwrap().breaks.push(jump);
+            }
+            Node::SetItemIndex(ident, index, expr) => {
+                self.expr(index);
+                self.expr(expr);
+                self.load(ident);
+                let cache = self.cache();
+                self.emit(Instruction::SetIndex(cache));
+            }
+            Node::Nonlocal(names) => self.resolver.nonlocal(names),
+            Node::Empty => {}
+            expr => {
+                self.expr(expr);
+                self.emit(Instruction::Pop);
+            }
+        }
+    }
+
+    fn expr(&mut self, node: &Node) {
+        match node {
+            Node::Literal(literal) => self.literal(literal),
+            Node::BinExpr(BinOp::GetItem, nodes) => {
+                self.expr(&nodes.0);
+                match &nodes.1 {
+                    Node::Ident(name) => {
+                        let name = self.name(name);
+                        let cache = self.cache();
+                        self.emit(Instruction::GetAttr(name, cache));
+                    }
+                    Node::FuncCall(name, args) => {
+                        self.exprs(args);
+                        let name = self.name(name);
+                        let cache = self.cache();
+                        self.emit(Instruction::CallMethod(name, index(args.len()), cache));
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            Node::BinExpr(op, nodes) => {
+                self.expr(&nodes.0);
+                self.expr(&nodes.1);
+                let cache = self.cache();
+                self.emit(Instruction::BinaryOp(*op, cache));
+            }
+            Node::UnaryOp(op, expr) => {
+                self.expr(expr);
+                let cache = self.cache();
+                self.emit(Instruction::UnaryOp(*op, cache));
+            }
+            Node::Ident(name) => self.load(name),
+            Node::FuncCall(name, args) => {
+                self.load(name);
+                self.exprs(args);
+                self.emit(Instruction::Call(index(args.len())));
+            }
+            Node::Closure(params, body) => {
+                let function = self.function("closure".into(), params, body);
+                let index = index(self.code.functions.len());
+                self.code.functions.push(Arc::new(function));
+                self.emit(Instruction::MakeFunction(index));
+            }
+            Node::GetItemIndex(ident, expr) => {
+                self.expr(expr);
+                self.load(ident);
+                let cache = self.cache();
+                self.emit(Instruction::GetIndex(cache));
+            }
+            Node::Yield(expr) => {
+                self.expr(expr);
+                self.emit(Instruction::Yield);
+            }
+            Node::Try(expr) => {
+                self.expr(expr);
+                self.emit(Instruction::Try);
+            }
+            statement => {
+                self.statement(statement);
+                let null = self.constant(NULL.clone());
+                self.emit(Instruction::Constant(null));
+            }
+        }
+    }
+
+    fn exprs(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.expr(node);
+        }
+    }
+
+    fn block(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.statement(node);
+        }
+    }
+
+    fn loop_body(&mut self, nodes: &[Node], has_iterator: bool) {
+        self.loops.push(Loop {
+            breaks: Vec::new(),
+            has_iterator,
+        });
+        self.block(nodes);
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        let object = match literal {
+            #[allow(clippy::cast_precision_loss)]
+            Literal::Int(int) => PtyNum(*int as f64).into(),
+            Literal::Float(float) => PtyNum(*float).into(),
+            Literal::Null => NULL.clone(),
+            Literal::Bool(bool) => PtyBool::new(*bool),
+            Literal::String(string) => PtyStr(string.clone()).into(),
+            Literal::List(items) => {
+                self.exprs(items);
+                self.emit(Instruction::BuildList(index(items.len())));
+                return;
+            }
+        };
+        let constant = self.constant(object);
+        self.emit(Instruction::Constant(constant));
+    }
+
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        self.code.instructions.push(instruction);
+        self.code.instructions.len() - 1
+    }
+
+    /// Points the jump at `at` to the next instruction.
+    fn patch(&mut self, at: usize) {
+        let target = index(self.code.instructions.len());
+        match &mut self.code.instructions[at] {
+            Instruction::Jump(to) | Instruction::JumpIfFalse(to) | Instruction::ForIter(to, _) => {
+                *to = target;
+            }
+            instruction => unreachable!("{instruction:?} is not a jump"),
+        }
+    }
+
+    fn patch_breaks(&mut self) {
+        let current = self.loops.pop().expect("Not in a loop");
+        for jump in current.breaks {
+            self.patch(jump);
+        }
+    }
+
+    fn load(&mut self, name: &Arc<str>) {
+        let instruction = match self.resolver.resolve(name) {
+            Resolved::Local(slot) => Instruction::LoadLocal(slot),
+            Resolved::Captured(slot) => Instruction::LoadCaptured(slot),
+            Resolved::Global => Instruction::LoadGlobal(self.name(name)),
+        };
+        self.emit(instruction);
+    }
+
+    fn store(&mut self, name: &Arc<str>) {
+        let instruction = match self.resolver.resolve_store(name) {
+            Resolved::Local(slot) => Instruction::StoreLocal(slot),
+            Resolved::Captured(slot) => Instruction::StoreCaptured(slot),
+            Resolved::Global => Instruction::StoreGlobal(self.name(name)),
+        };
+        self.emit(instruction);
+    }
+
+    fn name(&mut self, name: &Arc<str>) -> Index {
+        let position = self.code.names.iter().position(|known| known == name);
+        index(position.unwrap_or_else(|| {
+            self.code.names.push(name.clone());
+            self.code.names.len() - 1
+        }))
+    }
+
+    fn cache(&mut self) -> Index {
+        self.code.caches.push(InlineCache::default());
+        index(self.code.caches.len() - 1)
+    }
+
+    fn constant(&mut self, object: PettyObject) -> Index {
+        self.code.constants.push(object);
+        index(self.code.constants.len() - 1)
+    }
+}
+
+fn index(index: usize) -> Index {
+    Index::try_from(index).expect("Too many instructions in a single function")
+}
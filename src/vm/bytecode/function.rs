@@ -0,0 +1,73 @@
+use super::instruction::FunctionCode;
+use super::interpreter::{execute, Cell, Frame};
+use crate::vm::generator::PtyGenerator;
+use crate::vm::prelude::*;
+
+/// A function created by the bytecode interpreter, the counterpart of `PettyFunction`.
+#[derive(Clone)]
+pub struct CompiledFunction {
+    function: Arc<FunctionCode>,
+    /// The variables named by `function.captures`, shared with the frames defining them.
+    captures: Arc<[Cell]>,
+}
+
+impl CompiledFunction {
+    pub fn new(function: Arc<FunctionCode>, captures: Arc<[Cell]>) -> Self {
+        Self { function, captures }
+    }
+    pub fn name(&self) -> &Arc<str> {
+        &self.function.name
+    }
+    pub fn code(&self) -> &FunctionCode {
+        &self.function
+    }
+    pub fn captures(&self) -> &[Cell] {
+        &self.captures
+    }
+    pub fn run(&self, vm: &mut Vm, args: &[&PettyObject]) -> PettyObject {
+        let mut frame = Frame::new(&self.function, &self.captures);
+        frame.bind(args.iter().map(|&arg| arg.clone()));
+        vm.enter_call();
+        let value = execute(vm, &self.function.code, &mut frame);
+        vm.exit_call();
+        value
+    }
+}
+
+impl PettyObjectType for CompiledFunction {
+    fn call(&self, vm: &mut Vm, _this: &PettyObject, args: FuncArgs) -> PettyObject {
+        if self.function.generator {
+            let function = self.clone();
+            let args: Vec<PettyObject> = args.0.iter().map(|&arg| arg.clone()).collect();
+            let body = Box::new(move |vm: &mut Vm| {
+                function.run(vm, &args.iter().collect::<Vec<_>>());
+            });
+            return PtyGenerator::new(vm.spawn_generator(), body).into();
+        }
+        self.run(vm, args.0)
+    }
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "__repr__" => __REPR__.clone(),
+            _ => panic!("function has no attribute {key}"),
+        }
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &CompiledFunction) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+impl fmt::Display for CompiledFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "function {} at {:?}",
+            self.function.name, self as *const Self
+        )
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use super::cache::InlineCache;
+use super::resolver::{Capture, Resolved};
+use crate::ast::{BinOp, UnaryOp};
+use crate::vm::object::PettyObject;
+
+/// Indexes into one of the tables of a [`Code`].
+pub type Index = u32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    /// Pushes `constants[index]`.
+    Constant(Index),
+    /// Pushes the local in `slot`.
+    LoadLocal(Index),
+    /// Pops a value into the local in `slot`.
+    StoreLocal(Index),
+    /// Pushes the variable captured in `slot` when the function was created.
+    LoadCaptured(Index),
+    /// Pops a value into the captured variable in `slot`, which is declared `nonlocal`.
+    StoreCaptured(Index),
+    /// Pushes the global named `names[index]`.
+    LoadGlobal(Index),
+    /// Pops a value into the global named `names[index]`.
+    StoreGlobal(Index),
+    /// Pops an object and pushes its item `names[index]`, looked up through `caches[cache]`.
+    GetAttr(Index, Index),
+    /// Calls the method `names[index]` on an object followed by `argc` arguments,
+    /// looked up through `caches[cache]`.
+    CallMethod(Index, u32, Index),
+    /// Calls a function followed by `argc` arguments.
+    Call(u32),
+    /// Returns the result of calling a function followed by `argc` arguments,
+    /// starting the current function over instead when that's the one called.
+    TailCall(u32),
+    /// Pops two operands, numbers skip the method lookup through `caches[cache]`.
+    BinaryOp(BinOp, Index),
+    UnaryOp(UnaryOp, Index),
+    /// Pops `len` items into a new list.
+    BuildList(u32),
+    /// Pops an index then an object and calls `__get_index__`, looked up through `caches[cache]`.
+    GetIndex(Index),
+    /// Pops an index, a value then an object and calls `__set_index__`,
+    /// looked up through `caches[cache]`.
+    SetIndex(Index),
+    Jump(Index),
+    /// Pops a value and jumps if it isn't truthy.
+    JumpIfFalse(Index),
+    /// Replaces an iterable with its iterator.
+    GetIter,
+    /// Pushes the next item of the iterator on top of the stack, looking up `__next__` through
+    /// `caches[cache]`. Once exhausted the iterator is popped and execution jumps to the target.
+    ForIter(Index, Index),
+    /// Pushes a new function from `functions[index]`, capturing the values it needs.
+    MakeFunction(Index),
+    /// Pushes a new class from `classes[index]`, creating its methods like functions.
+    MakeClass(Index),
+    Pop,
+    Return,
+    /// Pops a value to yield from the running generator, pushing the value it's resumed with.
+    Yield,
+    /// Unwraps `Some`/`Ok` on top of the stack, otherwise returns it from the function.
+    Try,
+}
+
+/// A compiled function body, or a single top level statement.
+#[derive(Default)]
+pub struct Code {
+    pub instructions: Vec<Instruction>,
+    pub constants: Vec<PettyObject>,
+    pub names: Vec<Arc<str>>,
+    pub functions: Vec<Arc<FunctionCode>>,
+    pub classes: Vec<ClassCode>,
+    /// One per instruction looking up items, see [`InlineCache`].
+    pub caches: Vec<InlineCache>,
+}
+
+pub struct FunctionCode {
+    pub name: Arc<str>,
+    /// The local slot of each parameter.
+    pub params: Vec<Index>,
+    pub code: Code,
+    /// Whether the body yields, making calls to it return a generator.
+    pub generator: bool,
+    /// The name of each local slot.
+    pub locals: Vec<Arc<str>>,
+    /// What each local reads until it's assigned, like the variable it shadows.
+    pub fallbacks: Vec<Option<Resolved>>,
+    /// Whether each local is captured by a nested function, keeping it in a shared cell.
+    pub cells: Vec<bool>,
+    /// The name of each captured value, and where the creating frame keeps it.
+    pub captures: Vec<(Arc<str>, Capture)>,
+}
+
+pub struct ClassCode {
+    pub fields: Arc<[Arc<str>]>,
+    pub methods: Arc<[Arc<FunctionCode>]>,
+}
@@ -0,0 +1,322 @@
+use super::function::CompiledFunction;
+use super::instruction::{Code, FunctionCode, Index, Instruction};
+use super::resolver::{Capture, Resolved};
+use crate::vm::petty_class::PettyClass;
+use crate::vm::prelude::*;
+
+/// A variable shared between the function defining it and the closures capturing it.
+pub type Cell = Arc<Mutex<Option<PettyObject>>>;
+
+/// A local slot, unset until assigned.
+enum Local {
+    Value(Option<PettyObject>),
+    Cell(Cell),
+}
+
+/// The variables of the function being executed, globals live in the `Vm`.
+#[derive(Default)]
+pub struct Frame<'a> {
+    function: Option<&'a FunctionCode>,
+    locals: Vec<Local>,
+    captures: &'a [Cell],
+}
+
+impl<'a> Frame<'a> {
+    pub fn new(function: &'a FunctionCode, captures: &'a [Cell]) -> Self {
+        let locals = function
+            .cells
+            .iter()
+            .map(|&cell| {
+                if cell {
+                    Local::Cell(Cell::default())
+                } else {
+                    Local::Value(None)
+                }
+            })
+            .collect();
+        Self {
+            function: Some(function),
+            locals,
+            captures,
+        }
+    }
+
+    fn function(&self) -> &'a FunctionCode {
+        self.function.expect("Top level code has no locals")
+    }
+
+    /// Stores `args` into the parameters.
+    pub fn bind(&mut self, args: impl ExactSizeIterator<Item = PettyObject>) {
+        let params = &self.function().params;
+        assert_eq!(
+            params.len(),
+            args.len(),
+            "Expected {} arguments, got {}.",
+            params.len(),
+            args.len()
+        );
+        for (&param, arg) in params.iter().zip(args) {
+            self.store_local(param, arg);
+        }
+    }
+
+    /// Whether this frame is running `function`, so a tail call to it can start it over.
+    fn is_running(&self, function: &CompiledFunction) -> bool {
+        self.function
+            .is_some_and(|code| std::ptr::eq(code, function.code()))
+            && std::ptr::eq(self.captures, function.captures())
+    }
+
+    pub fn store_local(&mut self, slot: Index, value: PettyObject) {
+        match &mut self.locals[slot as usize] {
+            Local::Value(local) => *local = Some(value),
+            Local::Cell(cell) => *cell.lock().unwrap() = Some(value),
+        }
+    }
+
+    fn load_local(&self, vm: &mut Vm, slot: Index) -> PettyObject {
+        let value = match &self.locals[slot as usize] {
+            Local::Value(value) => value.clone(),
+            Local::Cell(cell) => cell.lock().unwrap().clone(),
+        };
+        if let Some(value) = value {
+            return value;
+        }
+        let function = self.function();
+        let name = &function.locals[slot as usize];
+        match function.fallbacks[slot as usize] {
+            Some(Resolved::Captured(slot)) => self.load_captured(vm, slot),
+            Some(Resolved::Global) => load_global(vm, name),
+            _ => panic!("Not found: ({name})"),
+        }
+    }
+
+    fn load_captured(&self, vm: &mut Vm, slot: Index) -> PettyObject {
+        let value = self.captures[slot as usize].lock().unwrap().clone();
+        value.unwrap_or_else(|| load_global(vm, &self.function().captures[slot as usize].0))
+    }
+
+    fn store_captured(&self, slot: Index, value: PettyObject) {
+        *self.captures[slot as usize].lock().unwrap() = Some(value);
+    }
+
+    /// Shares the cells a new function from `function` captures out of this frame.
+    fn capture(&self, function: &FunctionCode) -> Arc<[Cell]> {
+        function
+            .captures
+            .iter()
+            .map(|(_, capture)| match *capture {
+                Capture::Local(slot) => match &self.locals[slot as usize] {
+                    Local::Cell(cell) => cell.clone(),
+                    Local::Value(_) => unreachable!("Captured locals are kept in cells"),
+                },
+                Capture::Captured(slot) => self.captures[slot as usize].clone(),
+            })
+            .collect()
+    }
+}
+
+fn load_global(vm: &mut Vm, name: &str) -> PettyObject {
+    vm.globals()
+        .get(name)
+        .unwrap_or_else(|| panic!("Not found: ({name})"))
+        .clone()
+}
+
+/// Runs `code` on `vm` with a fresh value stack, returning the value it returns.
+pub fn execute(vm: &mut Vm, code: &Code, frame: &mut Frame) -> PettyObject {
+    let mut stack: Vec<PettyObject> = Vec::new();
+    let mut pc = 0;
+    while let Some(&instruction) = code.instructions.get(pc) {
+        pc += 1;
+        vm.step();
+        match instruction {
+            Instruction::Constant(index) => stack.push(code.constants[index as usize].clone()),
+            Instruction::LoadLocal(slot) => stack.push(frame.load_local(vm, slot)),
+            Instruction::StoreLocal(slot) => frame.store_local(slot, pop(&mut stack)),
+            Instruction::LoadCaptured(slot) => stack.push(frame.load_captured(vm, slot)),
+            Instruction::StoreCaptured(slot) => frame.store_captured(slot, pop(&mut stack)),
+            Instruction::LoadGlobal(index) => {
+                stack.push(load_global(vm, &code.names[index as usize]));
+            }
+            Instruction::StoreGlobal(index) => {
+                let value = pop(&mut stack);
+                vm.globals()
+                    .insert(code.names[index as usize].clone(), value);
+            }
+            Instruction::GetAttr(index, cache) => {
+                let object = pop(&mut stack);
+                let name = &code.names[index as usize];
+                stack.push(code.caches[cache as usize].get_item(vm, &object, name));
+            }
+            Instruction::CallMethod(index, argc, cache) => {
+                let start = stack.len() - argc as usize;
+                let object = &stack[start - 1];
+                let name = &code.names[index as usize];
+                let function = code.caches[cache as usize].get_item(vm, object, name);
+                let receiver = object.downcast_ref::<Module>().is_none().then_some(object);
+                let value = call(vm, &function, receiver, &stack[start..]);
+                stack.truncate(start - 1);
+                stack.push(value);
+            }
+            Instruction::Call(argc) => {
+                let start = stack.len() - argc as usize;
+                let value = call(vm, &stack[start - 1], None, &stack[start..]);
+                stack.truncate(start - 1);
+                stack.push(value);
+            }
+            Instruction::TailCall(argc) => {
+                let start = stack.len() - argc as usize;
+                let function = &stack[start - 1];
+                if !function
+                    .downcast_ref::<CompiledFunction>()
+                    .is_some_and(|function| frame.is_running(function))
+                {
+                    return call(vm, function, None, &stack[start..]);
+                }
+                // Closures created by the previous run keep their own cells.
+                *frame = Frame::new(frame.function(), frame.captures);
+                frame.bind(stack.drain(start..));
+                stack.clear();
+                pc = 0;
+            }
+            Instruction::BinaryOp(op, cache) => {
+                let rhs = pop(&mut stack);
+                let lhs = pop(&mut stack);
+                if let Some(value) = PtyNum::binary_op(op, &lhs, &rhs) {
+                    stack.push(value);
+                    continue;
+                }
+                let name = op.into_petty_function();
+                let function = code.caches[cache as usize].get_item(vm, &lhs, name);
+                stack.push(function.call(vm, &function, FuncArgs(&[&lhs, &rhs])));
+            }
+            Instruction::UnaryOp(op, cache) => {
+                let inner = pop(&mut stack);
+                let name = op.into_petty_function();
+                let function = code.caches[cache as usize].get_item(vm, &inner, name);
+                stack.push(function.call(vm, &function, FuncArgs(&[&inner])));
+            }
+            Instruction::BuildList(len) => {
+                let items = stack.split_off(stack.len() - len as usize);
+                stack.push(vm.new_list(items).into());
+            }
+            Instruction::GetIndex(cache) => {
+                let object = pop(&mut stack);
+                let index = pop(&mut stack);
+                let function = code.caches[cache as usize].get_item(vm, &object, "__get_index__");
+                stack.push(function.call(vm, &function, FuncArgs(&[&object, &index, &object])));
+            }
+            Instruction::SetIndex(cache) => {
+                let object = pop(&mut stack);
+                let value = pop(&mut stack);
+                let index = pop(&mut stack);
+                let function = code.caches[cache as usize].get_item(vm, &object, "__set_index__");
+                let args = [&object, &index, &value, &object];
+                function.call(vm, &function, FuncArgs(&args));
+            }
+            Instruction::Jump(target) => pc = target as usize,
+            Instruction::JumpIfFalse(target) => {
+                let condition = pop(&mut stack);
+                if !PtyBool::truthy(vm, &condition) {
+                    pc = target as usize;
+                }
+            }
+            Instruction::GetIter => {
+                let iterable = pop(&mut stack);
+                stack.push(iterable.call_method(vm, "__iter__", FuncArgs(&[&iterable])));
+            }
+            Instruction::ForIter(target, cache) => {
+                let iter = stack.last().expect("Expected an iterator").clone();
+                let get_next = code.caches[cache as usize].get_item(vm, &iter, "__next__");
+                let next = get_next.call(vm, &get_next, FuncArgs(&[&iter]));
+                match next.downcast::<PtyOption>().unwrap().0 {
+                    Some(next) => stack.push(next),
+                    None => {
+                        stack.pop();
+                        pc = target as usize;
+                    }
+                }
+            }
+            Instruction::MakeFunction(index) => {
+                let function = &code.functions[index as usize];
+                let captures = frame.capture(function);
+                stack.push(CompiledFunction::new(function.clone(), captures).into());
+            }
+            Instruction::MakeClass(index) => {
+                let class = &code.classes[index as usize];
+                let methods = class
+                    .methods
+                    .iter()
+                    .map(|method| CompiledFunction::new(method.clone(), frame.capture(method)))
+                    .collect();
+                stack.push(PettyClass::compiled(class.fields.clone(), methods).into());
+            }
+            Instruction::Pop => {
+                stack.pop();
+            }
+            Instruction::Return => return pop(&mut stack),
+            Instruction::Yield => {
+                let value = pop(&mut stack);
+                let generator = vm
+                    .generator
+                    .as_ref()
+                    .expect("yield can only be used inside a function");
+                stack.push(generator.yield_value(value));
+            }
+            Instruction::Try => {
+                let value = pop(&mut stack);
+                assert!(
+                    frame.function.is_some(),
+                    "`?` can only be used inside a function"
+                );
+                if let Some(PtyOption(option)) = value.downcast_ref() {
+                    match option {
+                        Some(inner) => stack.push(inner.clone()),
+                        None => return NONE.clone(),
+                    }
+                } else if let Some(PtyResult(result)) = value.downcast_ref() {
+                    match result {
+                        Ok(inner) => stack.push(inner.clone()),
+                        Err(_) => return value,
+                    }
+                } else {
+                    panic!(
+                        "`?` expects an Option or Result, got {}",
+                        value.force_repr(vm)
+                    );
+                }
+            }
+        }
+    }
+    NULL.clone()
+}
+
+/// Calls `function` with `receiver` then `args`,
+/// keeping the references on the Rust stack for the usual few arguments.
+fn call(
+    vm: &mut Vm,
+    function: &PettyObject,
+    receiver: Option<&PettyObject>,
+    args: &[PettyObject],
+) -> PettyObject {
+    const INLINE: usize = 4;
+    let len = usize::from(receiver.is_some()) + args.len();
+    let mut inline = [function; INLINE];
+    let mut spilled;
+    let refs = if len <= INLINE {
+        &mut inline[..len]
+    } else {
+        spilled = vec![function; len];
+        &mut spilled[..]
+    };
+    for (slot, arg) in refs.iter_mut().zip(receiver.into_iter().chain(args)) {
+        *slot = arg;
+    }
+    function.call(vm, function, FuncArgs(refs))
+}
+
+#[inline]
+fn pop(stack: &mut Vec<PettyObject>) -> PettyObject {
+    stack.pop().expect("Stack underflow")
+}
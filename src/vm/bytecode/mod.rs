@@ -0,0 +1,31 @@
+//! A compiler from [`Node`]s to a compact instruction set, and a stack based interpreter for it.
+//! Runs the same objects and builtins as the tree walker, see [`Engine`](super::Engine).
+mod cache;
+mod compiler;
+mod function;
+mod instruction;
+mod interpreter;
+mod resolver;
+
+use super::core::Vm;
+use super::object::PettyObject;
+use crate::ast::Node;
+pub use compiler::Compiler;
+pub use function::CompiledFunction;
+pub use interpreter::{execute, Frame};
+
+/// Compiles and runs each top level statement, returning their values like `Vm::evaluate_list`.
+///
+/// # Panics
+/// If the program reads names that are never defined, before running any of it.
+pub fn run(vm: &mut Vm, nodes: &[Node]) -> Vec<PettyObject> {
+    let globals: Vec<_> = vm.globals().keys().cloned().collect();
+    let codes = Compiler::compile(globals, nodes).unwrap_or_else(|errors| {
+        let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        panic!("{}", errors.join("\n"))
+    });
+    codes
+        .iter()
+        .map(|code| execute(vm, code, &mut Frame::default()))
+        .collect()
+}
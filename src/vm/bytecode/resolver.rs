@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use indexmap::IndexSet;
+use thiserror::Error;
+
+use super::instruction::Index;
+use crate::ast::Node;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ResolveError {
+    #[error("Undefined name `{0}`")]
+    UndefinedName(Arc<str>),
+    #[error("No binding for nonlocal `{0}` in an enclosing function")]
+    Nonlocal(Arc<str>),
+}
+
+/// Where a name lives once resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolved {
+    /// A slot in the current function's locals.
+    Local(Index),
+    /// A variable of an enclosing function, shared with it through a cell.
+    Captured(Index),
+    /// A global variable or builtin, looked up by name.
+    Global,
+}
+
+/// Where a closure takes a captured value from, in the frame creating it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capture {
+    Local(Index),
+    Captured(Index),
+}
+
+/// The names bound inside a function body, in slot order, and what it captures.
+#[derive(Debug, Default)]
+pub struct FunctionScope {
+    pub locals: IndexSet<Arc<str>>,
+    /// Whether each local is captured by a nested function, so has to live in a cell.
+    pub cells: Vec<bool>,
+    pub captures: IndexSet<Arc<str>>,
+    pub capture_sources: Vec<Capture>,
+    /// What each local falls back to while it hasn't been assigned yet,
+    /// like reading an outer variable before shadowing it.
+    pub fallbacks: Vec<Option<Resolved>>,
+}
+
+impl FunctionScope {
+    fn capture(&mut self, name: &Arc<str>, source: Capture) -> Index {
+        let (index, new) = self.captures.insert_full(name.clone());
+        if new {
+            self.capture_sources.push(source);
+        }
+        slot(index)
+    }
+}
+
+/// Resolves names statically, scopes follow functions: blocks and loops don't introduce any.
+pub struct Resolver {
+    globals: IndexSet<Arc<str>>,
+    functions: Vec<FunctionScope>,
+    pub errors: Vec<ResolveError>,
+}
+
+impl Resolver {
+    /// `globals` are the names defined before the program runs, like builtins.
+    /// Names assigned at the top level of `program` are added to them.
+    pub fn new(globals: impl IntoIterator<Item = Arc<str>>, program: &[Node]) -> Self {
+        let mut globals: IndexSet<Arc<str>> = globals.into_iter().collect();
+        assigned_names(program, &mut globals);
+        Self {
+            globals,
+            functions: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn enter_function(&mut self, params: &[Arc<str>], body: &[Node]) {
+        let nonlocals = Node::nonlocals(body);
+        let mut locals: IndexSet<Arc<str>> = params.iter().cloned().collect();
+        assigned_names(body, &mut locals);
+        locals.retain(|name| !nonlocals.contains(name));
+        self.functions.push(FunctionScope {
+            cells: vec![false; locals.len()],
+            locals,
+            ..FunctionScope::default()
+        });
+        let depth = self.functions.len() - 1;
+        for name in nonlocals {
+            if !matches!(self.lookup_outer(&name, depth), Some(Resolved::Captured(_))) {
+                self.error(ResolveError::Nonlocal(name));
+            }
+        }
+    }
+
+    pub fn exit_function(&mut self) -> FunctionScope {
+        let depth = self
+            .functions
+            .len()
+            .checked_sub(1)
+            .expect("Not inside a function");
+        let locals = self.functions[depth].locals.clone();
+        let fallbacks = locals
+            .iter()
+            .map(|name| self.lookup_outer(name, depth))
+            .collect();
+        let mut scope = self.functions.pop().unwrap();
+        scope.fallbacks = fallbacks;
+        scope
+    }
+
+    /// Resolves a name being read, recording an error if it isn't bound anywhere.
+    pub fn resolve(&mut self, name: &Arc<str>) -> Resolved {
+        self.lookup(name).unwrap_or_else(|| {
+            self.error(ResolveError::UndefinedName(name.clone()));
+            Resolved::Global
+        })
+    }
+
+    /// Resolves a name being assigned, which is local inside a function unless declared `nonlocal`.
+    pub fn resolve_store(&mut self, name: &Arc<str>) -> Resolved {
+        match self.functions.len().checked_sub(1) {
+            Some(depth) => match self.functions[depth].locals.get_index_of(name) {
+                Some(index) => Resolved::Local(slot(index)),
+                // Reported when entering the function if it doesn't resolve to a capture.
+                None => self.lookup_outer(name, depth).unwrap_or(Resolved::Global),
+            },
+            None => Resolved::Global,
+        }
+    }
+
+    /// Reports a `nonlocal` statement at the top level,
+    /// inside functions they're resolved when entering the function.
+    pub fn nonlocal(&mut self, names: &[Arc<str>]) {
+        if self.functions.is_empty() {
+            for name in names {
+                self.error(ResolveError::Nonlocal(name.clone()));
+            }
+        }
+    }
+
+    fn error(&mut self, error: ResolveError) {
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+
+    fn lookup(&mut self, name: &Arc<str>) -> Option<Resolved> {
+        let Some(depth) = self.functions.len().checked_sub(1) else {
+            return self.globals.contains(name).then_some(Resolved::Global);
+        };
+        if let Some(index) = self.functions[depth].locals.get_index_of(name) {
+            return Some(Resolved::Local(slot(index)));
+        }
+        self.lookup_outer(name, depth)
+    }
+
+    /// Looks `name` up in the functions enclosing `depth`, capturing it through each level.
+    fn lookup_outer(&mut self, name: &Arc<str>, depth: usize) -> Option<Resolved> {
+        if let Some(index) = self.functions[depth].captures.get_index_of(name) {
+            return Some(Resolved::Captured(slot(index)));
+        }
+        let source = match depth.checked_sub(1) {
+            None => return self.globals.contains(name).then_some(Resolved::Global),
+            Some(outer) => match self.functions[outer].locals.get_index_of(name) {
+                Some(index) => {
+                    self.functions[outer].cells[index] = true;
+                    Capture::Local(slot(index))
+                }
+                None => match self.lookup_outer(name, outer)? {
+                    Resolved::Captured(index) => Capture::Captured(index),
+                    resolved => return Some(resolved),
+                },
+            },
+        };
+        Some(Resolved::Captured(
+            self.functions[depth].capture(name, source),
+        ))
+    }
+}
+
+/// Collects every name assigned in `nodes` without entering nested functions or classes.
+fn assigned_names(nodes: &[Node], names: &mut IndexSet<Arc<str>>) {
+    for node in nodes {
+        match node {
+            Node::SetEq(name, _) | Node::FuncDef(name, ..) | Node::ClassDef(name, ..) => {
+                names.insert(name.clone());
+            }
+            Node::ForLoop(target, _, block) => {
+                names.insert(target.clone());
+                assigned_names(block, names);
+            }
+            Node::Block(block) | Node::Globals(block) | Node::WhileLoop(_, block) => {
+                assigned_names(block, names);
+            }
+            Node::IfState(_, block, or_else) => {
+                assigned_names(block, names);
+                if let Some(or_else) = or_else {
+                    assigned_names(std::slice::from_ref(or_else), names);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn slot(index: usize) -> Index {
+    Index::try_from(index).expect("Too many names in a single function")
+}
@@ -1,25 +1,74 @@
 use super::{
-    dict::insert_ref, petty_class::PettyClass, petty_function::PettyFunction,
-    preallocated::PreAllocated, prelude::*,
+    dict::insert_ref,
+    generator::GeneratorContext,
+    limits::{Budget, Counted, Limits},
+    petty_class::PettyClass,
+    petty_function::PettyFunction,
+    preallocated::PreAllocated,
+    prelude::*,
 };
 use crate::ast::{BinOp, Literal, Node, UnaryOp};
-use std::{ops::Deref, sync::MutexGuard};
+use crate::error::RuntimeError;
+use std::{ops::Deref, panic, sync::MutexGuard};
+
+/// The bytes counted against the memory limit for each list item and dict entry.
+const LIST_ITEM: usize = std::mem::size_of::<PettyObject>();
+const DICT_ENTRY: usize = std::mem::size_of::<(u64, Arc<str>, PettyObject)>();
+
+/// Unwinds out of the function currently being run, carrying the value `?` returns early with.
+/// Caught by [`PettyFunction::run`].
+pub struct EarlyReturn(pub PettyObject);
 
 #[derive(Default, Clone)]
 pub struct VirtualMachine {
     pub preallocated: PreAllocated,
     pub globals: Arc<Mutex<Dict>>,
+    pub limits: Limits,
+    pub budget: Arc<Budget>,
+}
+
+/// The variables of one function call, shared with the closures created during it
+/// so they see its later assignments.
+#[derive(Default, Clone)]
+pub struct Scope {
+    vars: Arc<Mutex<Dict>>,
+    /// The names declared `nonlocal`, assigned in the enclosing scope defining them instead.
+    nonlocals: Arc<[Arc<str>]>,
+}
+
+impl Scope {
+    pub fn new(nonlocals: Arc<[Arc<str>]>) -> Self {
+        Self {
+            vars: Arc::default(),
+            nonlocals,
+        }
+    }
+    pub fn vars(&self) -> MutexGuard<'_, Dict> {
+        self.vars.lock().unwrap()
+    }
 }
 
 #[derive(Default)]
 pub struct Vm {
     pub inner: VirtualMachine,
     pub return_val: Option<PettyObject>,
-    pub scopes: Vec<Dict>,
+    pub scopes: Vec<Scope>,
+    /// Set while running the body of a generator, see [`GeneratorContext`].
+    pub generator: Option<GeneratorContext>,
+    /// How many script function calls are running on this vm's thread.
+    pub depth: usize,
+    /// The depth this vm's thread has stack for, below the recursion limit
+    /// for vms running on threads from [`Limits::spawn`](super::Limits::spawn).
+    pub max_depth: Option<usize>,
+    /// A function and arguments returned from in tail position, called by
+    /// [`PettyFunction::run`] once the current call is done with.
+    pub tail_call: Option<(PettyObject, Vec<PettyObject>)>,
+    /// The steps this vm can run before taking more from the [`Budget`].
+    fuel: u64,
 }
 
 impl VirtualMachine {
-    pub fn globals(&self) -> MutexGuard<Dict> {
+    pub fn globals(&self) -> MutexGuard<'_, Dict> {
         self.globals.lock().unwrap()
     }
 }
@@ -33,15 +82,25 @@ impl Vm {
         self.write(name.into(), object);
     }
     pub fn write_ref(&mut self, key: &Arc<str>, value: PettyObject) {
-        if let Some(scope) = self.scopes.last_mut() {
-            insert_ref(scope, key, value);
-        } else {
-            insert_ref(&mut self.globals(), key, value);
+        let Some((scope, enclosing)) = self.scopes.split_last() else {
+            return insert_ref(&mut self.globals(), key, value);
         };
+        if scope.nonlocals.contains(key) {
+            let owner = enclosing
+                .iter()
+                .rev()
+                .find(|scope| scope.vars().contains_key(key))
+                .unwrap_or_else(|| {
+                    panic!("No binding for nonlocal `{key}` in an enclosing function")
+                });
+            insert_ref(&mut owner.vars(), key, value);
+        } else {
+            insert_ref(&mut scope.vars(), key, value);
+        }
     }
     pub fn write(&mut self, key: Arc<str>, value: PettyObject) -> Option<PettyObject> {
-        match self.scopes.last_mut() {
-            Some(scope) => scope.insert(key, value),
+        match self.scopes.last() {
+            Some(scope) => scope.vars().insert(key, value),
             None => self.globals().insert(key, value),
         }
     }
@@ -49,7 +108,7 @@ impl Vm {
     /// - TODO
     pub fn read(&mut self, key: &str) -> PettyObject {
         for scope in self.scopes.iter().rev() {
-            if let Some(object) = scope.get(key) {
+            if let Some(object) = scope.vars().get(key) {
                 return object.clone();
             }
         }
@@ -60,7 +119,7 @@ impl Vm {
     }
 
     pub fn new_scope(&mut self) {
-        self.scopes.push(Dict::new());
+        self.scopes.push(Scope::default());
     }
 
     pub fn drop_scope(&mut self) {
@@ -74,14 +133,122 @@ impl Vm {
             inner: self.inner.clone(),
             return_val: None,
             scopes: vec![],
+            generator: None,
+            depth: 0,
+            max_depth: Some(self.inner.limits.thread_recursion),
+            tail_call: None,
+            fuel: 0,
         }
     }
+
+    /// A vm for a generator's body, which runs on its own thread
+    /// but counts as nested in the call creating it.
+    #[must_use]
+    pub fn spawn_generator(&self) -> Self {
+        Self {
+            depth: self.depth,
+            max_depth: Some(
+                self.depth
+                    .saturating_add(self.inner.limits.thread_recursion),
+            ),
+            ..self.spawn_new()
+        }
+    }
+
+    /// Counts a script function call, raising a `RecursionError` past the limit.
+    pub fn enter_call(&mut self) {
+        let limit = self.inner.limits.recursion;
+        let limit = self
+            .max_depth
+            .map_or(limit, |max_depth| max_depth.min(limit));
+        if self.depth >= limit {
+            RuntimeError::Recursion { limit }.raise();
+        }
+        self.depth += 1;
+    }
+
+    pub fn exit_call(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Raises a `MemoryError` if allocating `bytes` more for strings and containers
+    /// would go past the memory limit, for operations about to allocate that much.
+    pub fn check_memory(&self, bytes: usize) {
+        self.inner.budget.check(&self.inner.limits, bytes);
+    }
+
+    /// A string counted against the memory limit while it's alive.
+    pub fn new_str(&self, string: impl AsRef<str> + Into<Arc<str>>) -> PettyObject {
+        self.build_str(string.as_ref().len(), || string)
+    }
+
+    /// A string of `len` bytes made by `build`, failing with a `MemoryError`
+    /// before building it if it doesn't fit in the memory limit.
+    pub fn build_str<S: Into<Arc<str>>>(
+        &self,
+        len: usize,
+        build: impl FnOnce() -> S,
+    ) -> PettyObject {
+        self.inner.budget.reserve(&self.inner.limits, len);
+        let string = PtyStr(build().into());
+        Counted::holding(string, self.inner.budget.memory(), len).into()
+    }
+
+    /// A list counted against the memory limit while it's alive.
+    pub fn new_list(&self, items: Vec<PettyObject>) -> PtyList {
+        let len = items.len();
+        let list = PtyList::new(items);
+        self.grow_list(&list, len);
+        list
+    }
+
+    /// A dict counted against the memory limit while it's alive.
+    pub fn new_dict(&self, map: DictMap) -> PtyDict {
+        let len = map.len();
+        let dict = PtyDict::new(map);
+        self.grow_dict(&dict, len);
+        dict
+    }
+
+    /// Counts `added` more items of `list` against the memory limit, before adding them.
+    pub fn grow_list(&self, list: &PtyList, added: usize) {
+        let bytes = added.saturating_mul(LIST_ITEM);
+        self.inner.budget.reserve(&self.inner.limits, bytes);
+        list.0.hold(self.inner.budget.memory(), bytes);
+    }
+
+    /// Gives back what was counted for `removed` items taken out of `list`.
+    pub fn shrink_list(&self, list: &PtyList, removed: usize) {
+        list.0.release(removed.saturating_mul(LIST_ITEM));
+    }
+
+    /// Counts `added` more entries of `dict` against the memory limit, before adding them.
+    pub fn grow_dict(&self, dict: &PtyDict, added: usize) {
+        let bytes = added.saturating_mul(DICT_ENTRY);
+        self.inner.budget.reserve(&self.inner.limits, bytes);
+        dict.0.hold(self.inner.budget.memory(), bytes);
+    }
+
+    /// Gives back what was counted for `removed` entries taken out of `dict`.
+    pub fn shrink_dict(&self, dict: &PtyDict, removed: usize) {
+        dict.0.release(removed.saturating_mul(DICT_ENTRY));
+    }
+
+    /// Counts one step against the program's [`Budget`].
+    #[inline]
+    pub fn step(&mut self) {
+        if self.fuel == 0 {
+            self.fuel = self.inner.budget.refuel(&self.inner.limits);
+        }
+        self.fuel -= 1;
+    }
 }
 
 impl Vm {
     /// # Panics
     /// - TODO
     pub fn evaluate(&mut self, node: &Node) -> PettyObject {
+        self.step();
         match node {
             Node::Globals(nodes) | Node::Block(nodes) => self.execute_nodes(nodes),
             Node::SetEq(name, expr) => self.set_eq(name, expr),
@@ -97,7 +264,9 @@ impl Vm {
             Node::FuncDef(name, args, block) => {
                 self.func_def(name, args.clone(), block.clone());
             }
-            Node::ReturnState(expr) => self.return_val = Some(self.evaluate(expr)),
+            Node::ReturnState(expr) => self.return_statement(expr),
+            Node::Yield(expr) => return self.yield_value(expr),
+            Node::Try(expr) => return self.try_expr(expr),
             Node::UnaryOp(op, expr) => return self.unary_expr(*op, expr),
             Node::IfState(condition, block, or_else) => {
                 self.if_statement(condition, block, or_else.as_ref().map(Arc::as_ref));
@@ -109,6 +278,10 @@ impl Vm {
             }
             Node::Closure(params, body) => return self.closure(params.clone(), body.clone()),
             Node::GetItemIndex(ident, expr) => return self.get_item_index(ident, expr),
+            Node::Nonlocal(_) => assert!(
+                !self.scopes.is_empty(),
+                "nonlocal can only be used inside a function"
+            ),
             Node::SetItemIndex(ident, index, expr) => self.set_item_index(ident, index, expr),
             _ => todo!("{node:?}"),
         };
@@ -124,6 +297,22 @@ impl Vm {
         }
     }
 
+    fn return_statement(&mut self, expr: &Node) {
+        if let Node::FuncCall(name, args) = expr {
+            let function = self.read(name);
+            let args = self.evaluate_list(args);
+            if function.downcast_ref::<PettyFunction>().is_some() && !self.scopes.is_empty() {
+                self.tail_call = Some((function, args));
+                self.return_val = Some(NULL.clone());
+            } else {
+                let args: Vec<_> = args.iter().collect();
+                self.return_val = Some(function.call(self, &function, FuncArgs(&args)));
+            }
+            return;
+        }
+        self.return_val = Some(self.evaluate(expr));
+    }
+
     fn set_eq(&mut self, name: &Arc<str>, expr: &Node) {
         let value = self.evaluate(expr);
         self.write_ref(name, value);
@@ -151,6 +340,9 @@ impl Vm {
     fn bin_expr(&mut self, op: BinOp, lhs: &Node, rhs: &Node) -> PettyObject {
         let lhs = self.evaluate(lhs);
         let rhs = self.evaluate(rhs);
+        if let Some(result) = PtyNum::binary_op(op, &lhs, &rhs) {
+            return result;
+        }
         let function_name = op.into_petty_function();
         let function = lhs.get_item(self, &lhs, function_name);
         let binding = [&lhs, &rhs];
@@ -192,9 +384,7 @@ impl Vm {
 
     fn if_statement(&mut self, condition: &Node, block: &[Node], or_else: Option<&Node>) {
         let condition = self.evaluate(condition);
-        let condition = condition.call_method(self, "__bool__", FuncArgs(&[]));
-        let condition = condition.downcast_ref::<PtyBool>().expect("Expected Bool");
-        if condition.0 {
+        if PtyBool::truthy(self, &condition) {
             return self.execute_nodes(block);
         }
         if let Some(node) = or_else {
@@ -205,9 +395,7 @@ impl Vm {
     fn while_loop(&mut self, condition: &Node, block: &[Node]) {
         while self.return_val.is_none() && {
             let condition = self.evaluate(condition);
-            let condition = condition.call_method(self, "__bool__", FuncArgs(&[]));
-            let condition = condition.downcast_ref::<PtyBool>().expect("Expected Bool");
-            condition.0
+            PtyBool::truthy(self, &condition)
         } {
             for node in block {
                 if self.return_val.is_some() {
@@ -238,6 +426,44 @@ impl Vm {
         }
     }
 
+    fn yield_value(&mut self, expr: &Node) -> PettyObject {
+        let value = self.evaluate(expr);
+        let generator = self
+            .generator
+            .as_ref()
+            .expect("yield can only be used inside a function");
+        generator.yield_value(value)
+    }
+
+    /// Unwraps `Some`/`Ok`, otherwise returns the `None`/`Err` from the enclosing function.
+    fn try_expr(&mut self, expr: &Node) -> PettyObject {
+        let value = self.evaluate(expr);
+        if let Some(PtyOption(option)) = value.downcast_ref() {
+            return match option {
+                Some(inner) => inner.clone(),
+                None => self.early_return(NONE.clone()),
+            };
+        }
+        if let Some(PtyResult(result)) = value.downcast_ref() {
+            return match result {
+                Ok(inner) => inner.clone(),
+                Err(_) => self.early_return(value.clone()),
+            };
+        }
+        panic!(
+            "`?` expects an Option or Result, got {}",
+            value.force_repr(self)
+        )
+    }
+
+    fn early_return(&self, value: PettyObject) -> ! {
+        assert!(
+            !self.scopes.is_empty(),
+            "`?` can only be used inside a function"
+        );
+        panic::resume_unwind(Box::new(EarlyReturn(value)))
+    }
+
     fn class_def(&mut self, name: &Arc<str>, fields: Arc<[Arc<str>]>, methods: Arc<[Node]>) {
         let class = PettyClass::new(fields, methods);
         self.write_ref(name, class.into());
@@ -265,7 +491,10 @@ impl Vm {
             Literal::Null => NULL.clone(),
             Literal::Bool(bool) => PtyBool::new(*bool),
             Literal::String(string) => PtyStr(string.clone()).into(),
-            Literal::List(list) => PtyList(Mutex::new(self.evaluate_list(list)).into()).into(),
+            Literal::List(list) => {
+                let items = self.evaluate_list(list);
+                self.new_list(items).into()
+            }
         }
     }
 }
@@ -274,7 +503,7 @@ impl BinOp {
     #[must_use]
     #[inline]
     /// # Panics
-    fn into_petty_function(self) -> &'static str {
+    pub fn into_petty_function(self) -> &'static str {
         match self {
             Self::Add => "__add__",
             Self::Sub => "__sub__",
@@ -297,7 +526,7 @@ impl UnaryOp {
     #[must_use]
     #[inline]
     /// # Panics
-    fn into_petty_function(self) -> &'static str {
+    pub fn into_petty_function(self) -> &'static str {
         match self {
             Self::Neg => "__neg__",
             Self::Not => "__not__",
@@ -0,0 +1,182 @@
+use super::{builtins::iterator, prelude::*};
+use std::{
+    panic,
+    sync::mpsc::{channel, Receiver, Sender},
+    thread::{self, JoinHandle},
+};
+
+/// Unwinds a generator's thread once nothing can resume it anymore.
+struct GeneratorExit;
+
+/// The generator's side of the channels, stored on the [`Vm`] running its body.
+pub struct GeneratorContext {
+    resume: Receiver<PettyObject>,
+    yields: Sender<PettyObject>,
+}
+
+impl GeneratorContext {
+    /// Hands `value` to whoever resumed the generator and waits to be resumed again,
+    /// returning the value passed to `send`.
+    pub fn yield_value(&self, value: PettyObject) -> PettyObject {
+        if self.yields.send(value).is_err() {
+            panic::resume_unwind(Box::new(GeneratorExit));
+        }
+        self.resume
+            .recv()
+            .unwrap_or_else(|_| panic::resume_unwind(Box::new(GeneratorExit)))
+    }
+}
+
+/// Runs the generator's function body, on the generator's own thread.
+pub type Body = Box<dyn FnOnce(&mut Vm) + Send>;
+
+enum State {
+    /// Not resumed yet, the body only starts running on the first `__next__`.
+    Pending(Box<Vm>, Body),
+    Running {
+        resume: Sender<PettyObject>,
+        yields: Receiver<PettyObject>,
+        thread: JoinHandle<()>,
+    },
+    Finished,
+}
+
+/// Returned when calling a function which contains `yield`.
+/// The body runs on its own thread, taking turns with the caller so only one side runs at a time.
+#[derive(Clone)]
+pub struct PtyGenerator(Arc<Mutex<State>>);
+
+impl PtyGenerator {
+    pub fn new(vm: Vm, body: Body) -> Self {
+        Self(Mutex::new(State::Pending(Box::new(vm), body)).into())
+    }
+
+    /// Runs the body until its next `yield`, `None` once it has returned.
+    pub fn resume(&self, value: PettyObject) -> Option<PettyObject> {
+        // The lock is held while the body runs, so the body can't resume itself.
+        let mut state = self.0.try_lock().expect("Generator is already running");
+        match std::mem::replace(&mut *state, State::Finished) {
+            State::Pending(vm, body) => {
+                assert!(
+                    value.downcast_ref::<PtyNull>().is_some(),
+                    "Can't send a value to a generator that hasn't started"
+                );
+                *state = Self::start(vm, body);
+            }
+            State::Running {
+                resume,
+                yields,
+                thread,
+            } => {
+                if resume.send(value).is_err() {
+                    propagate(thread.join());
+                    return None;
+                }
+                *state = State::Running {
+                    resume,
+                    yields,
+                    thread,
+                };
+            }
+            State::Finished => return None,
+        }
+        let State::Running { yields, .. } = &*state else {
+            unreachable!()
+        };
+        if let Ok(value) = yields.recv() {
+            return Some(value);
+        }
+        // The body returned or panicked, either way the generator is done.
+        if let State::Running { thread, .. } = std::mem::replace(&mut *state, State::Finished) {
+            propagate(thread.join());
+        }
+        None
+    }
+
+    /// Stops the generator, unwinding its body from the `yield` it is paused at.
+    pub fn close(&self) {
+        let state = std::mem::replace(&mut *self.0.lock().unwrap(), State::Finished);
+        if let State::Running { resume, thread, .. } = state {
+            drop(resume);
+            propagate(thread.join());
+        }
+    }
+
+    fn start(mut vm: Box<Vm>, body: Body) -> State {
+        let limits = vm.inner.limits;
+        let (resume, resume_receiver) = channel();
+        let (yields_sender, yields) = channel();
+        let thread = limits.spawn(move || {
+            vm.generator = Some(GeneratorContext {
+                resume: resume_receiver,
+                yields: yields_sender,
+            });
+            body(&mut vm);
+        });
+        State::Running {
+            resume,
+            yields,
+            thread,
+        }
+    }
+}
+
+/// Re-raises a panic from the generator's body on the thread that resumed it.
+fn propagate(result: thread::Result<()>) {
+    if let Err(payload) = result {
+        if !payload.is::<GeneratorExit>() {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+impl PettyObjectType for PtyGenerator {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, str: &str) -> PettyObject {
+        match str {
+            "__next__" | "next" => __NEXT__.clone(),
+            "__iter__" | "iter" => __ITER__.clone(),
+            "__repr__" => __REPR__.clone(),
+            "send" => SEND.clone(),
+            "close" => CLOSE.clone(),
+            _ => iterator::method(str).unwrap_or_else(|| panic!("generator has no attribute {str}")),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("generator is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for PtyGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "generator at {:?}", self as *const Self)
+    }
+}
+
+#[pettymethod]
+fn __next__(this: &PtyGenerator) -> PettyObject {
+    PtyOption::new(this.resume(NULL.clone()))
+}
+
+/// Resumes the generator, making the paused `yield` evaluate to `value`.
+#[pettymethod]
+fn send(this: &PtyGenerator, value: &PettyObject) -> PettyObject {
+    PtyOption::new(this.resume(value.clone()))
+}
+
+#[pettymethod]
+fn close(this: &PtyGenerator) {
+    this.close();
+}
+
+#[pettymethod]
+fn __iter__(this: PtyGenerator) -> PtyGenerator {
+    this
+}
+
+#[pettymethod]
+fn __repr__(this: &PtyGenerator) -> PtyStr {
+    PtyStr(format!("{this}").into())
+}
@@ -0,0 +1,330 @@
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use super::{core::Vm, function_args::FuncArgs, object::PettyObjectType, PettyObject};
+use crate::error::RuntimeError;
+
+/// Rust stack taken by each nested script call.
+const PER_CALL: usize = 32 << 10;
+
+/// Bounds put on a running program, exceeding one aborts it with a [`RuntimeError`].
+///
+/// [`RuntimeError`]: crate::error::RuntimeError
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// How many script function calls can be nested on one thread.
+    pub recursion: usize,
+    /// How many calls a generator or `std.thread` thread can nest on top of the depth
+    /// it starts at, which sizes its stack. Lower than `recursion` so that holding
+    /// thousands of them doesn't reserve a full size stack for each.
+    pub thread_recursion: usize,
+    /// How many steps the program can run across all its threads, a step being
+    /// an evaluated node or an executed bytecode instruction.
+    pub steps: Option<u64>,
+    /// How long the program can run for.
+    pub timeout: Option<Duration>,
+    /// How many bytes the program's strings and containers can hold at once.
+    pub memory: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            recursion: 10_000,
+            thread_recursion: 256,
+            steps: None,
+            timeout: None,
+            memory: None,
+        }
+    }
+}
+
+impl Limits {
+    /// Reads the limits from the environment, `PETTY_RECURSION_LIMIT` for the recursion limit,
+    /// `PETTY_THREAD_RECURSION_LIMIT` for the thread recursion limit, `PETTY_STEP_LIMIT` for the steps, `PETTY_TIMEOUT_MS` for the timeout in milliseconds
+    /// and `PETTY_MEMORY_LIMIT` for the memory in bytes.
+    /// # Panics
+    /// If a variable is set to something other than a number.
+    #[must_use]
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            let value = std::env::var(name).ok()?;
+            let parsed = value.parse();
+            Some(parsed.unwrap_or_else(|_| panic!("Invalid {name} {value:?}")))
+        }
+        let mut limits = Self::default();
+        if let Some(recursion) = var("PETTY_RECURSION_LIMIT") {
+            limits.recursion = recursion;
+        }
+        if let Some(thread_recursion) = var("PETTY_THREAD_RECURSION_LIMIT") {
+            limits.thread_recursion = thread_recursion;
+        }
+        limits.steps = var("PETTY_STEP_LIMIT");
+        limits.timeout = var("PETTY_TIMEOUT_MS").map(Duration::from_millis);
+        limits.memory = var("PETTY_MEMORY_LIMIT");
+        limits
+    }
+
+    /// The stack the main vm thread needs to reach the recursion limit,
+    /// each nested call takes a few evaluation frames on the Rust stack.
+    pub fn stack_size(&self) -> usize {
+        const BASE: usize = 8 << 20;
+        self.recursion.saturating_mul(PER_CALL).saturating_add(BASE)
+    }
+
+    /// The stack of a generator or `std.thread` thread, enough for `thread_recursion` calls.
+    pub fn thread_stack_size(&self) -> usize {
+        const BASE: usize = 2 << 20;
+        self.thread_recursion
+            .saturating_mul(PER_CALL)
+            .saturating_add(BASE)
+    }
+
+    /// Runs `f` on a new thread with a stack deep enough for `thread_recursion` calls,
+    /// the vm it runs is expected to have its `max_depth` set to match.
+    /// # Panics
+    /// If the thread can't be created.
+    pub fn spawn<T, F>(&self, f: F) -> JoinHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        thread::Builder::new()
+            .stack_size(self.thread_stack_size())
+            .spawn(f)
+            .expect("Failed to spawn a thread")
+    }
+}
+
+/// Stops a running program from another thread, see [`run_cancellable`].
+///
+/// [`run_cancellable`]: super::run_cancellable
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the program fail with [`RuntimeError::Cancelled`] the next time it checks its budget.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// What's left of the steps, time and memory a program can use, shared by all its threads.
+///
+/// Each vm takes steps out of it in chunks, checking the deadline and cancellation in between,
+/// so a blocking call like `std.thread.sleep` finishes before the program is stopped.
+#[derive(Debug)]
+pub struct Budget {
+    /// The steps not handed out to a vm yet.
+    steps: AtomicU64,
+    deadline: Option<Instant>,
+    cancel: CancelHandle,
+    memory: Arc<Memory>,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::new(&Limits::default(), CancelHandle::new())
+    }
+}
+
+impl Budget {
+    /// How many steps a vm runs before checking in.
+    const CHUNK: u64 = 1024;
+
+    /// Starts counting the program's budget from now.
+    pub fn new(limits: &Limits, cancel: CancelHandle) -> Self {
+        Self {
+            steps: AtomicU64::new(limits.steps.unwrap_or(u64::MAX)),
+            deadline: limits.timeout.map(|timeout| Instant::now() + timeout),
+            cancel,
+            memory: Arc::default(),
+        }
+    }
+
+    /// Hands out the next steps a vm can run, raising the error
+    /// of the first of `limits` that's been exceeded.
+    pub fn refuel(&self, limits: &Limits) -> u64 {
+        if self.cancel.is_cancelled() {
+            RuntimeError::Cancelled.raise();
+        }
+        if let (Some(deadline), Some(limit)) = (self.deadline, limits.timeout) {
+            if Instant::now() >= deadline {
+                RuntimeError::Timeout { limit }.raise();
+            }
+        }
+        let taken = self
+            .steps
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                (left > 0).then(|| left - left.min(Self::CHUNK))
+            });
+        match taken {
+            Ok(left) => left.min(Self::CHUNK),
+            Err(_) => RuntimeError::Steps {
+                limit: limits.steps.unwrap_or(u64::MAX),
+            }
+            .raise(),
+        }
+    }
+
+    /// Counts `bytes` about to be allocated, raising a `MemoryError` instead
+    /// if they'd take the program past `limits.memory`.
+    /// They're held until given to a buffer with [`Counted::hold`] and it's dropped.
+    pub fn reserve(&self, limits: &Limits, bytes: usize) {
+        if !self.memory.reserve(bytes, limits.memory) {
+            let limit = limits.memory.unwrap_or(usize::MAX);
+            RuntimeError::Memory { limit }.raise();
+        }
+    }
+
+    /// Raises a `MemoryError` if reserving `bytes` would, without reserving them.
+    pub fn check(&self, limits: &Limits, bytes: usize) {
+        if let Some(limit) = limits.memory {
+            if self.memory.used().saturating_add(bytes) > limit {
+                RuntimeError::Memory { limit }.raise();
+            }
+        }
+    }
+
+    /// The counter the program's strings and containers give their memory back to.
+    pub fn memory(&self) -> &Arc<Memory> {
+        &self.memory
+    }
+}
+
+/// The bytes held by a program's strings and containers that are still alive.
+#[derive(Debug, Default)]
+pub struct Memory {
+    used: AtomicUsize,
+}
+
+impl Memory {
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Counts `bytes` if they fit in `limit`.
+    fn reserve(&self, bytes: usize, limit: Option<usize>) -> bool {
+        let Some(limit) = limit else {
+            self.used.fetch_add(bytes, Ordering::Relaxed);
+            return true;
+        };
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(bytes).filter(|&used| used <= limit)
+            })
+            .is_ok()
+    }
+
+    fn release(&self, bytes: usize) {
+        self.used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+/// A string or container buffer, giving the bytes counted for it back to
+/// its program's [`Memory`] when it's dropped.
+pub struct Counted<T> {
+    value: T,
+    held: AtomicUsize,
+    /// Set by the first [`Counted::hold`], buffers that never grow don't need it.
+    memory: OnceLock<Arc<Memory>>,
+}
+
+impl<T> Counted<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            held: AtomicUsize::new(0),
+            memory: OnceLock::new(),
+        }
+    }
+
+    /// A buffer holding `bytes` reserved from `memory` with [`Budget::reserve`].
+    pub fn holding(value: T, memory: &Arc<Memory>, bytes: usize) -> Self {
+        Self {
+            value,
+            held: AtomicUsize::new(bytes),
+            memory: OnceLock::from(memory.clone()),
+        }
+    }
+
+    /// Takes over `bytes` reserved from `memory` with [`Budget::reserve`].
+    pub fn hold(&self, memory: &Arc<Memory>, bytes: usize) {
+        if Arc::ptr_eq(self.memory.get_or_init(|| memory.clone()), memory) {
+            self.held.fetch_add(bytes, Ordering::Relaxed);
+        } else {
+            // Only reachable by a host passing objects between programs,
+            // which only count the growth of buffers they were the first to grow.
+            memory.release(bytes);
+        }
+    }
+
+    /// Gives back up to `bytes` of what this buffer holds, once the items they counted are removed.
+    pub fn release(&self, bytes: usize) {
+        let Some(memory) = self.memory.get() else {
+            return;
+        };
+        let held = self
+            .held
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |held| {
+                Some(held.saturating_sub(bytes))
+            })
+            .unwrap_or_else(|held| held);
+        memory.release(held.min(bytes));
+    }
+}
+
+impl<T> Deref for Counted<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> Drop for Counted<T> {
+    fn drop(&mut self) {
+        if let Some(memory) = self.memory.get() {
+            memory.release(*self.held.get_mut());
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Counted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// Strings are counted as whole objects, which look like the string they wrap.
+impl<T: PettyObjectType> PettyObjectType for Counted<T> {
+    fn get_item(&self, vm: &mut Vm, this: &PettyObject, key: &str) -> PettyObject {
+        self.value.get_item(vm, this, key)
+    }
+    fn call(&self, vm: &mut Vm, this: &PettyObject, args: FuncArgs) -> PettyObject {
+        self.value.call(vm, this, args)
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self.value.as_any()
+    }
+    fn static_items(&self) -> bool {
+        self.value.static_items()
+    }
+}
@@ -1,10 +1,18 @@
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use self::limits::Budget;
 use self::object::PettyObject;
 use crate::ast::{self, Node};
+use crate::error::RuntimeError;
 
 mod builtins;
+mod bytecode;
 mod core;
 mod dict;
 mod function_args;
+mod generator;
+mod limits;
 mod object;
 mod petty_class;
 mod petty_function;
@@ -14,11 +22,96 @@ mod raw_function;
 mod stdlib;
 mod tests;
 
+pub use limits::{CancelHandle, Limits};
+pub use stdlib::set_args;
+
+/// Which interpreter runs the program, both share the same objects and builtins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Engine {
+    #[default]
+    TreeWalk,
+    Bytecode,
+}
+
+impl Engine {
+    /// Reads the engine from the `PETTY_ENGINE` environment variable, `tree` or `bytecode`.
+    /// # Panics
+    /// If the variable is set to anything else.
+    #[must_use]
+    pub fn from_env() -> Self {
+        match std::env::var("PETTY_ENGINE").as_deref() {
+            Err(_) | Ok("tree") => Self::TreeWalk,
+            Ok("bytecode") => Self::Bytecode,
+            Ok(other) => panic!("Unknown engine {other:?}, expected `tree` or `bytecode`"),
+        }
+    }
+}
+
+/// Whether call sites cache their item lookups and number operators skip dispatch.
+static FAST_DISPATCH: AtomicBool = AtomicBool::new(true);
+
+/// Turns the dispatch caches and number fast paths on or off for every engine.
+/// They are always on unless turned off to measure what they save, see `benches/dispatch.rs`.
+pub fn set_fast_dispatch(enabled: bool) {
+    FAST_DISPATCH.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn fast_dispatch() -> bool {
+    FAST_DISPATCH.load(Ordering::Relaxed)
+}
+
 pub fn run_virtual_machine(ast: &ast::Node) -> Vec<PettyObject> {
+    run_with_engine(ast, Engine::TreeWalk)
+}
+
+/// # Panics
+/// If the program exceeds the default [`Limits`].
+pub fn run_with_engine(ast: &ast::Node, engine: Engine) -> Vec<PettyObject> {
+    run_with_limits(ast, engine, Limits::default()).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Runs the program on a thread with a stack deep enough for `limits`,
+/// returning the error that aborted it if it exceeded one.
+/// # Panics
+/// If the program panics for any other reason.
+pub fn run_with_limits(
+    ast: &ast::Node,
+    engine: Engine,
+    limits: Limits,
+) -> Result<Vec<PettyObject>, RuntimeError> {
+    run_cancellable(ast, engine, limits, &CancelHandle::new())
+}
+
+/// Like [`run_with_limits`], also failing with [`RuntimeError::Cancelled`]
+/// once `cancel` is triggered from another thread.
+/// # Panics
+/// If the program panics for any other reason.
+pub fn run_cancellable(
+    ast: &ast::Node,
+    engine: Engine,
+    limits: Limits,
+    cancel: &CancelHandle,
+) -> Result<Vec<PettyObject>, RuntimeError> {
     let mut vm = core::Vm::new();
+    vm.inner.limits = limits;
+    vm.inner.budget = Budget::new(&limits, cancel.clone()).into();
     builtins::load_builtins(&mut vm);
-    match ast {
-        Node::Block(nodes) | Node::Globals(nodes) => vm.evaluate_list(nodes),
-        node => vec![vm.evaluate(node)],
-    }
+    let nodes = match ast {
+        Node::Block(nodes) | Node::Globals(nodes) => nodes,
+        node => std::slice::from_ref(node),
+    };
+    let result = std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(limits.stack_size())
+            .spawn_scoped(scope, move || match engine {
+                Engine::TreeWalk => vm.evaluate_list(nodes),
+                Engine::Bytecode => bytecode::run(&mut vm, nodes),
+            })
+            .expect("Failed to spawn the vm thread")
+            .join()
+    });
+    result.or_else(|payload| match payload.downcast::<RuntimeError>() {
+        Ok(error) => Err(*error),
+        Err(payload) => panic::resume_unwind(payload),
+    })
 }
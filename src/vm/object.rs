@@ -9,6 +9,11 @@ pub trait PettyObjectType: fmt::Display + Sync + Send {
     fn get_item(&self, vm: &mut Vm, this: &PettyObject, key: &str) -> PettyObject;
     fn call(&self, vm: &mut Vm, this: &PettyObject, args: FuncArgs) -> PettyObject;
     fn as_any(&self) -> &dyn std::any::Any;
+    /// Whether `get_item` returns the same item for a key on every object of this type,
+    /// letting call sites cache it by type. Modules and class instances have their own items.
+    fn static_items(&self) -> bool {
+        false
+    }
 }
 /// An actually petty object.
 #[derive(Clone)]
@@ -4,6 +4,7 @@ use std::{fmt, sync::Arc};
 
 use super::{
     builtins::{self, PtyStr},
+    bytecode::CompiledFunction,
     core::Vm,
     dict::Dict,
     function_args::FuncArgs,
@@ -18,11 +19,26 @@ pub struct PettyClassInstance {
 }
 pub struct PettyClass {
     pub fields: Arc<[Arc<str>]>,
-    pub methods: Arc<[Node]>,
+    pub methods: Methods,
+}
+/// The method definitions, turned into functions for each new instance.
+pub enum Methods {
+    Ast(Arc<[Node]>),
+    /// Created when the class is, capturing the variables of its definition.
+    Bytecode(Arc<[CompiledFunction]>),
 }
 impl PettyClass {
     pub fn new(fields: Arc<[Arc<str>]>, methods: Arc<[Node]>) -> Self {
-        Self { fields, methods }
+        Self {
+            fields,
+            methods: Methods::Ast(methods),
+        }
+    }
+    pub fn compiled(fields: Arc<[Arc<str>]>, methods: Arc<[CompiledFunction]>) -> Self {
+        Self {
+            fields,
+            methods: Methods::Bytecode(methods),
+        }
     }
 }
 impl PettyClassInstance {
@@ -69,12 +85,21 @@ impl PettyObjectType for PettyClass {
             .cloned()
             .zip(args.0.iter().copied().cloned())
             .collect();
-        for function in self.methods.iter().cloned() {
-            let Node::FuncDef(name, params, body) = function else {
-            unreachable!();
-        };
-            let function = PettyFunction::new(params, body, vm.scopes.clone());
-            fields.insert(name, function.into());
+        match &self.methods {
+            Methods::Ast(methods) => {
+                for function in methods.iter().cloned() {
+                    let Node::FuncDef(name, params, body) = function else {
+                        unreachable!();
+                    };
+                    let function = PettyFunction::new(params, body, vm.scopes.clone());
+                    fields.insert(name, function.into());
+                }
+            }
+            Methods::Bytecode(methods) => {
+                for function in methods.iter() {
+                    fields.insert(function.name().clone(), function.clone().into());
+                }
+            }
         }
         PettyClassInstance::new(fields).into()
     }
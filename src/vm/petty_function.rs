@@ -1,49 +1,107 @@
 use super::{
     builtins::NULL,
-    core::Vm,
-    dict::Dict,
+    core::{EarlyReturn, Scope, Vm},
     function_args::FuncArgs,
+    generator::PtyGenerator,
     object::{PettyObject, PettyObjectType},
 };
 use crate::ast::Node;
-use std::{fmt, sync::Arc};
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
 
 #[derive(Clone)]
 pub struct PettyFunction {
     args: Arc<[Arc<str>]>,
     block: Arc<[Node]>,
-    scopes: Vec<Dict>,
+    /// The scopes enclosing the definition, shared rather than copied.
+    scopes: Vec<Scope>,
+    generator: bool,
+    nonlocals: Arc<[Arc<str>]>,
 }
 impl PettyFunction {
-    pub fn new(args: Arc<[Arc<str>]>, block: Arc<[Node]>, scopes: Vec<Dict>) -> Self {
+    pub fn new(args: Arc<[Arc<str>]>, block: Arc<[Node]>, scopes: Vec<Scope>) -> Self {
         Self {
+            generator: Node::contains_yield(&block),
+            nonlocals: Node::nonlocals(&block).into(),
             args,
             block,
             scopes,
         }
     }
-}
-impl PettyObjectType for PettyFunction {
-    fn call(&self, vm: &mut Vm, _this: &PettyObject, args: FuncArgs) -> PettyObject {
-        for scope in &self.scopes {
-            vm.scopes.push(scope.clone());
+    /// Runs the body on `vm`, a generator's body is run this way from its own thread.
+    pub fn run(&self, vm: &mut Vm, args: &[&PettyObject]) -> PettyObject {
+        vm.enter_call();
+        // The body sees the scopes it was defined in, not the caller's,
+        // which also keeps lookups from slowing down as calls nest.
+        let caller = std::mem::replace(&mut vm.scopes, self.scopes.clone());
+        let mut result = self.execute(vm, args.iter().map(|&arg| arg.clone()));
+        let mut tail_call = vm.tail_call.take();
+        // A tail call to this same function runs the body again instead of nesting,
+        // so tail recursive loops run in constant stack space.
+        while let Some((_, args)) = tail_call.take_if(|(function, _)| self.is(function)) {
+            vm.return_val = None;
+            result = self.execute(vm, args.into_iter());
+            tail_call = vm.tail_call.take();
         }
-        vm.scopes.push(Dict::new());
-        if self.args.len() != args.0.len() {
+        vm.scopes = caller;
+        let value = match (result, tail_call) {
+            (Ok(()), Some((function, args))) => {
+                vm.return_val = None;
+                let args: Vec<_> = args.iter().collect();
+                function.call(vm, &function, FuncArgs(&args))
+            }
+            (Ok(()), None) => vm.return_val.take().unwrap_or_else(|| NULL.clone()),
+            (Err(payload), _) => match payload.downcast::<EarlyReturn>() {
+                Ok(early_return) => early_return.0,
+                Err(payload) => panic::resume_unwind(payload),
+            },
+        };
+        vm.exit_call();
+        value
+    }
+
+    /// Runs the body once in a new scope binding `args`, catching the unwinding of `?`.
+    fn execute(
+        &self,
+        vm: &mut Vm,
+        args: impl ExactSizeIterator<Item = PettyObject>,
+    ) -> std::thread::Result<()> {
+        if self.args.len() != args.len() {
             todo!(
                 "Expected {} arguments, got {}.",
                 { self.args.len() },
-                args.0.len()
+                args.len()
             );
         }
-        for (param, &arg) in self.args.iter().zip(args.0.iter()) {
-            vm.write_ref(param, arg.clone());
+        vm.scopes.push(Scope::new(self.nonlocals.clone()));
+        for (param, arg) in self.args.iter().zip(args) {
+            vm.write_ref(param, arg);
         }
-        vm.execute_nodes(&self.block);
-        for _ in 0..=self.scopes.len() {
-            vm.drop_scope();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| vm.execute_nodes(&self.block)));
+        vm.drop_scope();
+        result
+    }
+
+    fn is(&self, function: &PettyObject) -> bool {
+        function
+            .downcast_ref::<Self>()
+            .is_some_and(|function| std::ptr::eq(function, self))
+    }
+}
+impl PettyObjectType for PettyFunction {
+    fn call(&self, vm: &mut Vm, _this: &PettyObject, args: FuncArgs) -> PettyObject {
+        if self.generator {
+            let function = self.clone();
+            let args: Vec<PettyObject> = args.0.iter().map(|&arg| arg.clone()).collect();
+            let body = Box::new(move |vm: &mut Vm| {
+                function.run(vm, &args.iter().collect::<Vec<_>>());
+            });
+            return PtyGenerator::new(vm.spawn_generator(), body).into();
         }
-        vm.return_val.take().unwrap_or_else(|| NULL.clone())
+        self.run(vm, args.0)
     }
     fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, _str: &str) -> PettyObject {
         todo!()
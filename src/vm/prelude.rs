@@ -2,6 +2,7 @@ pub use super::builtins::*;
 pub use super::core::Vm;
 pub use super::dict::Dict;
 pub use super::function_args::FuncArgs;
+pub use super::limits::Counted;
 pub use super::object::{PettyObject, PettyObjectType};
 pub use crate::vm::raw_function::RawFunction;
 pub use macros::pettymethod;
@@ -0,0 +1,31 @@
+use crate::vm::prelude::*;
+
+pub fn init() -> Module {
+    let dict = Dict::from([
+        ("usage".into(), USAGE.clone()),
+        ("limit".into(), LIMIT.clone()),
+    ]);
+    Module {
+        name: "gc".into(),
+        dict: Arc::new(Mutex::new(dict)),
+    }
+}
+
+/// The bytes held by the program's strings and containers that are still alive.
+#[pettymethod]
+fn usage(vm: &mut Vm) -> PtyNum {
+    #[allow(clippy::cast_precision_loss)]
+    PtyNum(vm.inner.budget.memory().used() as f64)
+}
+
+/// The memory limit in bytes, `None` without one.
+#[pettymethod]
+fn limit(vm: &mut Vm) -> Option<PettyObject> {
+    #[allow(clippy::cast_precision_loss)]
+    let limit = vm
+        .inner
+        .limits
+        .memory
+        .map(|limit| PtyNum(limit as f64).into());
+    limit
+}
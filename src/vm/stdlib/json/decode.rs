@@ -0,0 +1,235 @@
+use thiserror::Error;
+
+use crate::vm::prelude::*;
+
+#[derive(Debug, Clone, Error, PartialEq)]
+#[error("{message} at line {line}, column {column}")]
+pub struct JsonError {
+    pub message: &'static str,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Decodes a JSON document into petty objects.
+/// Objects become `PtyDict`s, arrays `PtyList`s and numbers `PtyNum`s,
+/// counted against `vm`'s memory limit.
+pub fn decode(vm: &Vm, text: &str) -> Result<PettyObject, JsonError> {
+    let mut decoder = Decoder { vm, text, pos: 0 };
+    let value = decoder.value()?;
+    decoder.skip_whitespace();
+    if decoder.pos != text.len() {
+        return Err(decoder.error("Trailing characters"));
+    }
+    Ok(value)
+}
+
+struct Decoder<'a> {
+    vm: &'a Vm,
+    text: &'a str,
+    pos: usize,
+}
+
+impl Decoder<'_> {
+    fn error(&self, message: &'static str) -> JsonError {
+        let before = &self.text[..self.pos];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        JsonError {
+            message,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+    fn expect(&mut self, expected: char, message: &'static str) -> Result<(), JsonError> {
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+    fn skip_whitespace(&mut self) {
+        let rem = &self.text[self.pos..];
+        self.pos += rem.len() - rem.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+    fn keyword(&mut self, keyword: &str, value: PettyObject) -> Result<PettyObject, JsonError> {
+        if self.text[self.pos..].starts_with(keyword) {
+            self.pos += keyword.len();
+            Ok(value)
+        } else {
+            Err(self.error("Invalid literal"))
+        }
+    }
+
+    fn value(&mut self) -> Result<PettyObject, JsonError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => Ok(self.vm.new_str(self.string()?)),
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.keyword("true", TRUE.clone()),
+            Some('f') => self.keyword("false", FALSE.clone()),
+            Some('n') => self.keyword("null", NULL.clone()),
+            Some(_) => Err(self.error("Expected value")),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+    fn object(&mut self) -> Result<PettyObject, JsonError> {
+        self.expect('{', "Expected '{'")?;
+        let mut map = DictMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(self.vm.new_dict(map).into());
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("Expected string key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':', "Expected ':'")?;
+            let value = self.value()?;
+            map.insert(key.into(), value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+        Ok(self.vm.new_dict(map).into())
+    }
+    fn array(&mut self) -> Result<PettyObject, JsonError> {
+        self.expect('[', "Expected '['")?;
+        let mut list = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(self.vm.new_list(list).into());
+        }
+        loop {
+            list.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+        Ok(self.vm.new_list(list).into())
+    }
+    fn number(&mut self) -> Result<PettyObject, JsonError> {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let digits = |pos: &mut usize| {
+            let begin = *pos;
+            while bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+                *pos += 1;
+            }
+            *pos - begin
+        };
+        let mut pos = self.pos;
+        if bytes[pos] == b'-' {
+            pos += 1;
+        }
+        let int_start = pos;
+        if digits(&mut pos) == 0 || (bytes[int_start] == b'0' && pos - int_start > 1) {
+            self.pos = int_start;
+            return Err(self.error("Invalid number"));
+        }
+        if bytes.get(pos) == Some(&b'.') {
+            pos += 1;
+            if digits(&mut pos) == 0 {
+                self.pos = pos;
+                return Err(self.error("Expected digit after '.'"));
+            }
+        }
+        if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+            pos += 1;
+            if matches!(bytes.get(pos), Some(b'+' | b'-')) {
+                pos += 1;
+            }
+            if digits(&mut pos) == 0 {
+                self.pos = pos;
+                return Err(self.error("Expected digit in exponent"));
+            }
+        }
+        self.pos = pos;
+        let number = self.text[start..pos].parse().unwrap();
+        Ok(PtyNum(number).into())
+    }
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"', "Expected '\"'")?;
+        let mut string = String::new();
+        loop {
+            let Some(char) = self.peek() else {
+                return Err(self.error("Unterminated string"));
+            };
+            if char <= '\u{1f}' {
+                return Err(self.error("Control character in string"));
+            }
+            self.pos += char.len_utf8();
+            match char {
+                '"' => break Ok(string),
+                '\\' => string.push(self.escape()?),
+                char => string.push(char),
+            }
+        }
+    }
+    fn escape(&mut self) -> Result<char, JsonError> {
+        let escape = match self.peek() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                self.pos += 1;
+                return self.unicode_escape();
+            }
+            _ => return Err(self.error("Invalid escape")),
+        };
+        self.pos += 1;
+        Ok(escape)
+    }
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("Invalid unicode escape"));
+        }
+        if !self.text[self.pos..].starts_with("\\u") {
+            return Err(self.error("Unpaired surrogate"));
+        }
+        self.pos += 2;
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("Unpaired surrogate"));
+        }
+        let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+        char::from_u32(code).ok_or_else(|| self.error("Invalid unicode escape"))
+    }
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let hex = self.text.get(self.pos..self.pos + 4);
+        let code = hex.and_then(|hex| {
+            let valid = hex.bytes().all(|byte| byte.is_ascii_hexdigit());
+            valid.then(|| u32::from_str_radix(hex, 16).ok()).flatten()
+        });
+        let code = code.ok_or_else(|| self.error("Invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
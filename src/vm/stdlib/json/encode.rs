@@ -0,0 +1,105 @@
+use std::fmt::Write;
+
+use crate::vm::prelude::*;
+
+/// Encodes a petty object as JSON, pretty printing with `indent` spaces when it is non-zero.
+/// # Panics
+/// If `value` contains an object with no JSON representation.
+pub fn encode(value: &PettyObject, indent: usize) -> String {
+    let mut encoder = Encoder {
+        output: String::new(),
+        indent,
+        depth: 0,
+    };
+    encoder.value(value);
+    encoder.output
+}
+
+struct Encoder {
+    output: String,
+    indent: usize,
+    depth: usize,
+}
+
+impl Encoder {
+    fn value(&mut self, value: &PettyObject) {
+        if let Some(PtyStr(string)) = value.downcast_ref() {
+            self.string(string);
+        } else if let Some(PtyNum(num)) = value.downcast_ref() {
+            if num.is_finite() {
+                write!(self.output, "{num}").unwrap();
+            } else {
+                self.output.push_str("null");
+            }
+        } else if let Some(PtyBool(bool)) = value.downcast_ref() {
+            write!(self.output, "{bool}").unwrap();
+        } else if let Some(PtyOption(option)) = value.downcast_ref() {
+            match option {
+                Some(inner) => self.value(inner),
+                None => self.output.push_str("null"),
+            }
+        } else if value.downcast_ref::<PtyNull>().is_some() {
+            self.output.push_str("null");
+        } else if let Some(PtyList(list)) = value.downcast_ref() {
+            let items = list.lock().unwrap().clone();
+            self.sequence('[', ']', &items, |encoder, item| encoder.value(item));
+        } else if let Some(PtyDict(dict)) = value.downcast_ref() {
+            let items: Vec<_> = dict.lock().unwrap().clone().into_iter().collect();
+            self.sequence('{', '}', &items, |encoder, (key, value)| {
+                encoder.string(key);
+                encoder.output.push(':');
+                if encoder.indent != 0 {
+                    encoder.output.push(' ');
+                }
+                encoder.value(value);
+            });
+        } else {
+            panic!("{value} cannot be converted to json");
+        }
+    }
+    fn sequence<T>(
+        &mut self,
+        open: char,
+        close: char,
+        items: &[T],
+        mut item: impl FnMut(&mut Self, &T),
+    ) {
+        self.output.push(open);
+        if items.is_empty() {
+            self.output.push(close);
+            return;
+        }
+        self.depth += 1;
+        for (index, value) in items.iter().enumerate() {
+            if index != 0 {
+                self.output.push(',');
+            }
+            self.newline();
+            item(self, value);
+        }
+        self.depth -= 1;
+        self.newline();
+        self.output.push(close);
+    }
+    fn newline(&mut self) {
+        if self.indent != 0 {
+            self.output.push('\n');
+            self.output.push_str(&" ".repeat(self.indent * self.depth));
+        }
+    }
+    fn string(&mut self, string: &str) {
+        self.output.push('"');
+        for char in string.chars() {
+            match char {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                '\u{0}'..='\u{1f}' => write!(self.output, "\\u{:04x}", char as u32).unwrap(),
+                char => self.output.push(char),
+            }
+        }
+        self.output.push('"');
+    }
+}
@@ -0,0 +1,44 @@
+mod decode;
+mod encode;
+mod tests;
+
+use crate::vm::prelude::*;
+
+pub use decode::decode;
+pub use encode::encode;
+
+pub fn init() -> Module {
+    let dict = Dict::from([
+        ("__repr__".into(), __REPR__.clone()),
+        ("parse".into(), PARSE.clone()),
+        ("stringify".into(), STRINGIFY.clone()),
+    ]);
+
+    Module {
+        name: "json".into(),
+        dict: Mutex::new(dict).into(),
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &Module) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+#[pettymethod]
+fn parse(text: &PtyStr, vm: &mut Vm) -> PettyObject {
+    decode(vm, &text.0).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// `stringify(value)` or `stringify(value, indent)`, where a missing, null or zero indent is compact.
+#[pettymethod]
+fn stringify(value: &PettyObject, vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let indent = match args.0.first() {
+        Some(indent) => indent
+            .downcast_ref::<PtyNum>()
+            .map(|n| n.0.max(0.0) as usize),
+        None => None,
+    };
+    vm.new_str(encode(value, indent.unwrap_or(0)))
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod json_tests {
+    use super::super::{decode, decode::JsonError, encode};
+    use crate::vm::core::Vm;
+
+    fn roundtrip(source: &str, indent: usize) -> String {
+        encode(&decode(&Vm::new(), source).unwrap(), indent)
+    }
+    fn error(source: &str) -> (usize, usize) {
+        let JsonError { line, column, .. } = decode(&Vm::new(), source).err().unwrap();
+        (line, column)
+    }
+    #[test]
+    fn compact() {
+        let source = r#"{"a": [1, 2.5, -3e2], "b": {"c": null, "d": true}, "e": "x\"y"}"#;
+        let expected = r#"{"a":[1,2.5,-300],"b":{"c":null,"d":true},"e":"x\"y"}"#;
+        assert_eq!(roundtrip(source, 0), expected);
+    }
+    #[test]
+    fn pretty() {
+        let source = r#"{"a": [1, {}], "b": []}"#;
+        let expected = "{\n  \"a\": [\n    1,\n    {}\n  ],\n  \"b\": []\n}";
+        assert_eq!(roundtrip(source, 2), expected);
+    }
+    #[test]
+    fn unicode_escapes() {
+        assert_eq!(roundtrip(r#""é😀\n""#, 0), "\"é😀\\n\"");
+    }
+    #[test]
+    fn error_positions() {
+        assert_eq!(error("[1, 2,]"), (1, 7));
+        assert_eq!(error("{\n  \"a\": tru\n}"), (2, 8));
+        assert_eq!(error("[1]\n  x"), (2, 3));
+        assert_eq!(error("\"abc"), (1, 5));
+        assert_eq!(error("01"), (1, 1));
+        assert_eq!(error(r#""\q""#), (1, 3));
+    }
+    #[test]
+    fn multibyte_and_truncated_errors() {
+        assert_eq!(error("{\"a\":1é}"), (1, 7));
+        assert_eq!(error("[1é]"), (1, 3));
+        assert_eq!(error(r#""\é""#), (1, 3));
+        assert_eq!(error("{\"é\": 1"), (1, 8));
+        assert_eq!(error("{\"a\":1"), (1, 7));
+        assert_eq!(error("[1,"), (1, 4));
+        assert_eq!(error("\"\\"), (1, 3));
+        assert_eq!(error("\"\\u12"), (1, 4));
+    }
+}
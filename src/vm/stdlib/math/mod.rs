@@ -0,0 +1,222 @@
+use std::f64::consts;
+
+use crate::vm::prelude::*;
+
+pub fn init() -> Module {
+    let dict = Dict::from([
+        ("__repr__".into(), __REPR__.clone()),
+        ("pi".into(), PtyNum(consts::PI).into()),
+        ("tau".into(), PtyNum(consts::TAU).into()),
+        ("e".into(), PtyNum(consts::E).into()),
+        ("inf".into(), PtyNum(f64::INFINITY).into()),
+        ("nan".into(), PtyNum(f64::NAN).into()),
+        ("sqrt".into(), SQRT.clone()),
+        ("cbrt".into(), CBRT.clone()),
+        ("pow".into(), POW.clone()),
+        ("exp".into(), EXP.clone()),
+        ("ln".into(), LN.clone()),
+        ("log".into(), LOG.clone()),
+        ("log2".into(), LOG2.clone()),
+        ("log10".into(), LOG10.clone()),
+        ("sin".into(), SIN.clone()),
+        ("cos".into(), COS.clone()),
+        ("tan".into(), TAN.clone()),
+        ("asin".into(), ASIN.clone()),
+        ("acos".into(), ACOS.clone()),
+        ("atan".into(), ATAN.clone()),
+        ("atan2".into(), ATAN2.clone()),
+        ("sinh".into(), SINH.clone()),
+        ("cosh".into(), COSH.clone()),
+        ("tanh".into(), TANH.clone()),
+        ("asinh".into(), ASINH.clone()),
+        ("acosh".into(), ACOSH.clone()),
+        ("atanh".into(), ATANH.clone()),
+        ("floor".into(), FLOOR.clone()),
+        ("ceil".into(), CEIL.clone()),
+        ("round".into(), ROUND.clone()),
+        ("trunc".into(), TRUNC.clone()),
+        ("min".into(), MIN.clone()),
+        ("max".into(), MAX.clone()),
+        ("clamp".into(), CLAMP.clone()),
+        ("hypot".into(), HYPOT.clone()),
+        ("gcd".into(), GCD.clone()),
+        ("lcm".into(), LCM.clone()),
+        ("is_nan".into(), IS_NAN.clone()),
+        ("is_finite".into(), IS_FINITE.clone()),
+        ("is_inf".into(), IS_INF.clone()),
+    ]);
+
+    Module {
+        name: "math".into(),
+        dict: Mutex::new(dict).into(),
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &Module) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+#[pettymethod]
+fn sqrt(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.sqrt())
+}
+#[pettymethod]
+fn cbrt(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.cbrt())
+}
+#[pettymethod]
+fn pow(base: PtyNum, exp: PtyNum) -> PtyNum {
+    PtyNum(base.0.powf(exp.0))
+}
+#[pettymethod]
+fn exp(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.exp())
+}
+#[pettymethod]
+fn ln(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.ln())
+}
+/// `log(x)` is the natural logarithm, `log(x, base)` uses the given base.
+#[pettymethod]
+fn log(x: PtyNum, args: FuncArgs) -> PtyNum {
+    match args.0.first() {
+        Some(base) => PtyNum(x.0.log(number(base))),
+        None => PtyNum(x.0.ln()),
+    }
+}
+#[pettymethod]
+fn log2(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.log2())
+}
+#[pettymethod]
+fn log10(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.log10())
+}
+#[pettymethod]
+fn sin(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.sin())
+}
+#[pettymethod]
+fn cos(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.cos())
+}
+#[pettymethod]
+fn tan(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.tan())
+}
+#[pettymethod]
+fn asin(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.asin())
+}
+#[pettymethod]
+fn acos(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.acos())
+}
+#[pettymethod]
+fn atan(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.atan())
+}
+#[pettymethod]
+fn atan2(y: PtyNum, x: PtyNum) -> PtyNum {
+    PtyNum(y.0.atan2(x.0))
+}
+#[pettymethod]
+fn sinh(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.sinh())
+}
+#[pettymethod]
+fn cosh(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.cosh())
+}
+#[pettymethod]
+fn tanh(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.tanh())
+}
+#[pettymethod]
+fn asinh(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.asinh())
+}
+#[pettymethod]
+fn acosh(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.acosh())
+}
+#[pettymethod]
+fn atanh(x: PtyNum) -> PtyNum {
+    PtyNum(x.0.atanh())
+}
+/// Accepts either several numbers or a single list of numbers.
+#[pettymethod]
+fn min(args: FuncArgs) -> PtyNum {
+    PtyNum(numbers(args).into_iter().fold(f64::INFINITY, f64::min))
+}
+/// Accepts either several numbers or a single list of numbers.
+#[pettymethod]
+fn max(args: FuncArgs) -> PtyNum {
+    PtyNum(numbers(args).into_iter().fold(f64::NEG_INFINITY, f64::max))
+}
+#[pettymethod]
+fn clamp(x: PtyNum, min: PtyNum, max: PtyNum) -> PtyNum {
+    assert!(min.0 <= max.0, "clamp: min must not be greater than max");
+    PtyNum(x.0.clamp(min.0, max.0))
+}
+#[pettymethod]
+fn hypot(x: PtyNum, y: PtyNum) -> PtyNum {
+    PtyNum(x.0.hypot(y.0))
+}
+#[pettymethod]
+fn gcd(a: PtyNum, b: PtyNum) -> PtyNum {
+    #[allow(clippy::cast_precision_loss)]
+    PtyNum(int_gcd(integer(a), integer(b)) as f64)
+}
+#[pettymethod]
+fn lcm(a: PtyNum, b: PtyNum) -> PtyNum {
+    let (a, b) = (integer(a), integer(b));
+    if a == 0 || b == 0 {
+        return PtyNum(0.0);
+    }
+    #[allow(clippy::cast_precision_loss)]
+    PtyNum((a / int_gcd(a, b) * b) as f64)
+}
+#[pettymethod]
+fn is_nan(x: PtyNum) -> PettyObject {
+    PtyBool::new(x.0.is_nan())
+}
+#[pettymethod]
+fn is_finite(x: PtyNum) -> PettyObject {
+    PtyBool::new(x.0.is_finite())
+}
+#[pettymethod]
+fn is_inf(x: PtyNum) -> PettyObject {
+    PtyBool::new(x.0.is_infinite())
+}
+
+fn number(object: &PettyObject) -> f64 {
+    object.downcast_ref::<PtyNum>().expect("Expected Number").0
+}
+
+fn numbers(args: FuncArgs) -> Vec<f64> {
+    if let [list] = args.0 {
+        if let Some(PtyList(list)) = list.downcast_ref() {
+            return list.lock().unwrap().iter().map(number).collect();
+        }
+    }
+    args.0.iter().copied().map(number).collect()
+}
+
+fn integer(num: PtyNum) -> u128 {
+    assert!(
+        num.0.is_finite() && num.0.fract() == 0.0,
+        "Expected an integer, got {num}"
+    );
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let int = num.0.abs() as u128;
+    int
+}
+
+fn int_gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
@@ -1,13 +1,29 @@
 mod fs;
+mod gc;
+mod json;
+mod math;
+mod os;
+mod process;
+mod random;
+mod re;
 mod test;
 mod thread;
 mod time;
 
 use super::prelude::*;
 
+pub use os::set_args;
+
 pub fn init() -> Module {
     let dict = Dict::from([
         ("fs".into(), fs::init().into()),
+        ("gc".into(), gc::init().into()),
+        ("json".into(), json::init().into()),
+        ("math".into(), math::init().into()),
+        ("os".into(), os::init().into()),
+        ("process".into(), process::init().into()),
+        ("random".into(), random::init().into()),
+        ("re".into(), re::init().into()),
         ("thread".into(), thread::init().into()),
         ("time".into(), time::init().into()),
         ("test".into(), test::init().into()),
@@ -0,0 +1,76 @@
+use std::env::consts;
+
+use crate::vm::prelude::*;
+
+/// Command line arguments handed to the script, starting with the script path.
+static ARGS: Lazy<Mutex<Vec<Arc<str>>>> = Lazy::new(|| Mutex::new(vec![]));
+
+pub fn set_args(args: Vec<String>) {
+    *ARGS.lock().unwrap() = args.into_iter().map(Arc::from).collect();
+}
+
+pub fn init() -> Module {
+    let args = ARGS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|arg| PtyStr(arg.clone()).into())
+        .collect();
+    let dict = Dict::from([
+        ("__repr__".into(), __REPR__.clone()),
+        ("args".into(), PtyList::new(args).into()),
+        ("env".into(), ENV.clone()),
+        ("set_env".into(), SET_ENV.clone()),
+        ("cwd".into(), CWD.clone()),
+        ("chdir".into(), CHDIR.clone()),
+        ("exit".into(), EXIT.clone()),
+        ("pid".into(), PID.clone()),
+        ("platform".into(), PtyStr(consts::OS.into()).into()),
+        ("family".into(), PtyStr(consts::FAMILY.into()).into()),
+        ("arch".into(), PtyStr(consts::ARCH.into()).into()),
+    ]);
+
+    Module {
+        name: "os".into(),
+        dict: Mutex::new(dict).into(),
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &Module) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+#[pettymethod]
+fn env(name: &PtyStr) -> Option<PettyObject> {
+    std::env::var(&*name.0)
+        .ok()
+        .map(|value| PtyStr::from(value).into())
+}
+
+#[pettymethod]
+fn set_env(name: &PtyStr, value: &PtyStr) {
+    std::env::set_var(&*name.0, &*value.0);
+}
+
+#[pettymethod]
+fn cwd() -> PtyStr {
+    let cwd = std::env::current_dir().unwrap();
+    PtyStr(cwd.to_string_lossy().into())
+}
+
+#[pettymethod]
+fn chdir(path: &PtyStr) {
+    std::env::set_current_dir(&*path.0).unwrap();
+}
+
+#[pettymethod]
+fn exit(code: PtyNum) {
+    #[allow(clippy::cast_possible_truncation)]
+    std::process::exit(code.0 as i32);
+}
+
+#[pettymethod]
+fn pid() -> PtyNum {
+    PtyNum(f64::from(std::process::id()))
+}
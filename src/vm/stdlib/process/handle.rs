@@ -0,0 +1,203 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    process::{Child, ChildStdin, ChildStdout, ExitStatus},
+    time::{Duration, Instant},
+};
+
+use crate::vm::prelude::*;
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+#[derive(Clone)]
+pub struct ProcessHandle {
+    pub id: u32,
+    pub child: Arc<Mutex<Child>>,
+    pub stdin: ProcessStdin,
+    pub stdout: ProcessStdout,
+}
+
+#[derive(Clone)]
+pub struct ProcessStdin(Arc<Mutex<Option<ChildStdin>>>);
+
+#[derive(Clone)]
+pub struct ProcessStdout(Arc<Mutex<Option<BufReader<ChildStdout>>>>);
+
+impl ProcessHandle {
+    pub fn new(mut child: Child) -> Self {
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take().map(BufReader::new);
+        Self {
+            id: child.id(),
+            child: Mutex::new(child).into(),
+            stdin: ProcessStdin(Mutex::new(stdin).into()),
+            stdout: ProcessStdout(Mutex::new(stdout).into()),
+        }
+    }
+    /// Waits for the child to exit, giving up once `deadline` has passed.
+    /// The child is polled rather than waited on so that `kill` can still lock it.
+    fn wait_until(&self, deadline: Option<Instant>) -> Option<ExitStatus> {
+        loop {
+            if let Some(status) = self.child.lock().unwrap().try_wait().unwrap() {
+                return Some(status);
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return None;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl ProcessStdin {
+    fn close(&self) {
+        self.0.lock().unwrap().take();
+    }
+}
+
+impl PettyObjectType for ProcessHandle {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "wait" | "join" => WAIT.clone(),
+            "wait_timeout" => WAIT_TIMEOUT.clone(),
+            "kill" => KILL.clone(),
+            "pid" => PtyNum(f64::from(self.id)).into(),
+            "stdin" => self.stdin.clone().into(),
+            "stdout" => self.stdout.clone().into(),
+            "__repr__" => __REPR__.clone(),
+            _ => panic!("process has no attribute {key}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("process is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl PettyObjectType for ProcessStdin {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "write" => WRITE.clone(),
+            "close" => CLOSE.clone(),
+            "__repr__" => __REPR__.clone(),
+            _ => panic!("process stdin has no attribute {key}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("process stdin is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl PettyObjectType for ProcessStdout {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "__iter__" | "iter" | "lines" => __ITER__.clone(),
+            "__next__" | "next" | "read_line" => __NEXT__.clone(),
+            "read" => READ.clone(),
+            "__repr__" => __REPR__.clone(),
+            _ => panic!("process stdout has no attribute {key}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("process stdout is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for ProcessHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Process({})", self.id)
+    }
+}
+
+impl fmt::Display for ProcessStdin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Process Stdin at {:?}", self as *const Self)
+    }
+}
+
+impl fmt::Display for ProcessStdout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Process Stdout at {:?}", self as *const Self)
+    }
+}
+
+/// The exit code of a finished process, or the negated signal number if it was killed by one.
+pub fn exit_code(status: ExitStatus) -> f64 {
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return -f64::from(signal);
+    }
+    f64::from(status.code().unwrap_or(-1))
+}
+
+/// Closes stdin first so a child reading until EOF can finish.
+#[pettymethod]
+fn wait(this: &ProcessHandle) -> PtyNum {
+    this.stdin.close();
+    PtyNum(exit_code(this.wait_until(None).unwrap()))
+}
+
+#[pettymethod]
+fn wait_timeout(this: &ProcessHandle, timeout: PtyNum) -> Option<PettyObject> {
+    let deadline = Instant::now() + Duration::from_secs_f64(timeout.0.max(0.0));
+    this.wait_until(Some(deadline))
+        .map(|status| PtyNum(exit_code(status)).into())
+}
+
+#[pettymethod]
+fn kill(this: &ProcessHandle) {
+    // Killing a process that already exited is not an error worth surfacing.
+    let _ = this.child.lock().unwrap().kill();
+}
+
+#[pettymethod]
+fn write(this: &ProcessStdin, content: &PtyStr) {
+    let mut stdin = this.0.lock().unwrap();
+    let stdin = stdin.as_mut().expect("Stdin is closed");
+    stdin.write_all(content.0.as_bytes()).unwrap();
+    stdin.flush().unwrap();
+}
+
+#[pettymethod]
+fn close(this: &ProcessStdin) {
+    this.close();
+}
+
+#[pettymethod]
+fn __iter__(this: ProcessStdout) -> ProcessStdout {
+    this
+}
+
+#[pettymethod]
+fn __next__(this: &ProcessStdout) -> Option<PettyObject> {
+    let mut stdout = this.0.lock().unwrap();
+    let stdout = stdout.as_mut()?;
+    let mut line = String::new();
+    if stdout.read_line(&mut line).unwrap() == 0 {
+        return None;
+    }
+    let line = line.strip_suffix('\n').unwrap_or(&line);
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    Some(PtyStr(line.into()).into())
+}
+
+#[pettymethod]
+fn read(this: &ProcessStdout) -> PtyStr {
+    let mut buf = String::new();
+    if let Some(stdout) = this.0.lock().unwrap().as_mut() {
+        stdout.read_to_string(&mut buf).unwrap();
+    }
+    buf.into()
+}
+
+#[pettymethod]
+fn __repr__(this: &PettyObject) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
@@ -0,0 +1,98 @@
+mod handle;
+mod output;
+
+use std::{
+    io::{self, Read},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use crate::vm::prelude::*;
+
+use self::{handle::ProcessHandle, output::ProcessOutput};
+
+pub fn init() -> Module {
+    let dict = Dict::from([
+        ("__repr__".into(), __REPR__.clone()),
+        ("run".into(), RUN.clone()),
+        ("run_timeout".into(), RUN_TIMEOUT.clone()),
+        ("spawn".into(), SPAWN.clone()),
+    ]);
+
+    Module {
+        name: "process".into(),
+        dict: Mutex::new(dict).into(),
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &Module) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+#[pettymethod]
+fn run(cmd: &PtyStr, argv: &PtyList, vm: &mut Vm) -> ProcessOutput {
+    let output = command(vm, cmd, argv).output().unwrap();
+    ProcessOutput::new(output.status, &output.stdout, &output.stderr, false)
+}
+
+#[pettymethod]
+fn run_timeout(cmd: &PtyStr, argv: &PtyList, timeout: PtyNum, vm: &mut Vm) -> ProcessOutput {
+    let mut child = command(vm, cmd, argv)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Drain the pipes on their own threads so a chatty child can't block on a full pipe.
+    let stdout = read_to_end(child.stdout.take().unwrap());
+    let stderr = read_to_end(child.stderr.take().unwrap());
+
+    let deadline = Instant::now() + Duration::from_secs_f64(timeout.0.max(0.0));
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            timed_out = true;
+            let _ = child.kill();
+            break child.wait().unwrap();
+        }
+        std::thread::sleep(handle::POLL_INTERVAL);
+    };
+    let (stdout, stdout_read) = stdout.join().unwrap();
+    let (stderr, stderr_read) = stderr.join().unwrap();
+    ProcessOutput::new(status, &stdout, &stderr, timed_out)
+        .with_error(stdout_read.and(stderr_read).err())
+}
+
+#[pettymethod]
+fn spawn(cmd: &PtyStr, argv: &PtyList, vm: &mut Vm) -> ProcessHandle {
+    let child = command(vm, cmd, argv)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    ProcessHandle::new(child)
+}
+
+fn command(vm: &mut Vm, cmd: &PtyStr, argv: &PtyList) -> Command {
+    let args = argv.0.lock().unwrap().clone();
+    let mut command = Command::new(&*cmd.0);
+    for arg in args {
+        command.arg(&*arg.force_repr(vm).0);
+    }
+    command
+}
+
+/// Reads `pipe` to the end, keeping whatever arrived before a read error.
+fn read_to_end(
+    mut pipe: impl Read + Send + 'static,
+) -> std::thread::JoinHandle<(Vec<u8>, io::Result<()>)> {
+    std::thread::spawn(move || {
+        let mut buf = vec![];
+        let read = pipe.read_to_end(&mut buf).map(|_| ());
+        (buf, read)
+    })
+}
@@ -0,0 +1,68 @@
+use std::{io, process::ExitStatus};
+
+use crate::vm::prelude::*;
+
+use super::handle::exit_code;
+
+#[derive(Clone)]
+pub struct ProcessOutput {
+    status: Option<f64>,
+    success: bool,
+    stdout: PtyStr,
+    stderr: PtyStr,
+    timed_out: bool,
+    error: Option<PtyStr>,
+}
+
+impl ProcessOutput {
+    pub fn new(status: ExitStatus, stdout: &[u8], stderr: &[u8], timed_out: bool) -> Self {
+        Self {
+            status: (!timed_out).then(|| exit_code(status)),
+            success: status.success() && !timed_out,
+            stdout: PtyStr(String::from_utf8_lossy(stdout).into()),
+            stderr: PtyStr(String::from_utf8_lossy(stderr).into()),
+            timed_out,
+            error: None,
+        }
+    }
+    /// Records an error hit while reading the child's output.
+    pub fn with_error(mut self, error: Option<io::Error>) -> Self {
+        self.error = error.map(|error| PtyStr(error.to_string().into()));
+        self
+    }
+}
+
+impl PettyObjectType for ProcessOutput {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "status" => PtyOption::new(self.status.map(|code| PtyNum(code).into())),
+            "success" => PtyBool::new(self.success),
+            "stdout" => self.stdout.clone().into(),
+            "stderr" => self.stderr.clone().into(),
+            "timed_out" => PtyBool::new(self.timed_out),
+            "error" => PtyOption::new(self.error.clone().map(PettyObject::from)),
+            "__repr__" => __REPR__.clone(),
+            _ => panic!("process output has no attribute {key}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("process output is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for ProcessOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(code) => write!(f, "ProcessOutput(status: {code})"),
+            None => write!(f, "ProcessOutput(timed out)"),
+        }
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &ProcessOutput) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
@@ -0,0 +1,81 @@
+mod rng;
+
+use crate::vm::prelude::*;
+
+use self::rng::{PtyRng, Rng};
+
+/// The generator behind the module level functions, reseedable with `std.random.seed`.
+static DEFAULT: Lazy<PettyObject> = Lazy::new(|| PtyRng::new(Rng::from_entropy()).into());
+
+pub fn init() -> Module {
+    let dict = Dict::from([
+        ("__repr__".into(), __REPR__.clone()),
+        ("Rng".into(), rng::__INIT__.clone()),
+        ("default".into(), DEFAULT.clone()),
+        ("seed".into(), SEED.clone()),
+        ("random".into(), RANDOM.clone()),
+        ("randint".into(), RANDINT.clone()),
+        ("uniform".into(), UNIFORM.clone()),
+        ("choice".into(), CHOICE.clone()),
+        ("shuffle".into(), SHUFFLE.clone()),
+        ("sample".into(), SAMPLE.clone()),
+        ("gauss".into(), GAUSS.clone()),
+    ]);
+
+    Module {
+        name: "random".into(),
+        dict: Mutex::new(dict).into(),
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &Module) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+/// Forwards a module level call to the same method on the default generator.
+fn call_default(vm: &mut Vm, method: &PettyObject, args: FuncArgs) -> PettyObject {
+    let mut items = vec![&*DEFAULT];
+    items.extend_from_slice(args.0);
+    method.call(vm, method, FuncArgs(&items))
+}
+
+#[pettymethod]
+fn seed(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_default(vm, &rng::SEED, args)
+}
+
+#[pettymethod]
+fn random(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_default(vm, &rng::RANDOM, args)
+}
+
+#[pettymethod]
+fn randint(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_default(vm, &rng::RANDINT, args)
+}
+
+#[pettymethod]
+fn uniform(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_default(vm, &rng::UNIFORM, args)
+}
+
+#[pettymethod]
+fn choice(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_default(vm, &rng::CHOICE, args)
+}
+
+#[pettymethod]
+fn shuffle(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_default(vm, &rng::SHUFFLE, args)
+}
+
+#[pettymethod]
+fn sample(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_default(vm, &rng::SAMPLE, args)
+}
+
+#[pettymethod]
+fn gauss(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_default(vm, &rng::GAUSS, args)
+}
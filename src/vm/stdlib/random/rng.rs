@@ -0,0 +1,212 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::vm::prelude::*;
+
+/// xoshiro256** seeded through splitmix64, so a seed always produces the same sequence.
+#[derive(Clone)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn seeded(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut splitmix = || {
+            seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Self {
+            state: std::array::from_fn(|_| splitmix()),
+        }
+    }
+    pub fn from_entropy() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        #[allow(clippy::cast_possible_truncation)]
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64);
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self::seeded(nanos ^ count.rotate_left(32) ^ u64::from(std::process::id()))
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+        result
+    }
+    /// A float in `[0, 1)`.
+    pub fn random(&mut self) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let float = (self.next_u64() >> 11) as f64;
+        float / (1u64 << 53) as f64
+    }
+    /// An unbiased integer in `[0, bound)`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound != 0, "Cannot pick from an empty range");
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+    /// An integer in `[low, high]`, both ends inclusive.
+    pub fn randint(&mut self, low: f64, high: f64) -> f64 {
+        let (low, high) = (low.ceil(), high.floor());
+        assert!(low <= high, "randint: empty range [{low}, {high}]");
+        // The cast saturates, and a span covering every u64 needs no rejection sampling.
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let offset = match ((high - low) as u64).checked_add(1) {
+            Some(span) => self.below(span),
+            None => self.next_u64(),
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let offset = offset as f64;
+        low + offset
+    }
+    pub fn gauss(&mut self, mu: f64, sigma: f64) -> f64 {
+        // Box-Muller; `1 - random()` keeps the logarithm away from zero.
+        let radius = (-2.0 * (1.0 - self.random()).ln()).sqrt();
+        let angle = std::f64::consts::TAU * self.random();
+        mu + sigma * radius * angle.cos()
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = self.below(index as u64 + 1) as usize;
+            items.swap(index, other);
+        }
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn choice(&mut self, items: &[PettyObject]) -> PettyObject {
+        assert!(!items.is_empty(), "Cannot choose from an empty list");
+        items[self.below(items.len() as u64) as usize].clone()
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn sample(&mut self, mut items: Vec<PettyObject>, k: usize) -> Vec<PettyObject> {
+        assert!(k <= items.len(), "Sample larger than population");
+        for index in 0..k {
+            let other = index + self.below((items.len() - index) as u64) as usize;
+            items.swap(index, other);
+        }
+        items.truncate(k);
+        items
+    }
+}
+
+#[derive(Clone)]
+pub struct PtyRng(pub Arc<Mutex<Rng>>);
+
+impl PtyRng {
+    pub fn new(rng: Rng) -> Self {
+        Self(Mutex::new(rng).into())
+    }
+}
+
+/// Converts a script value into a seed from its bit pattern, so `0` and `-0` seed differently.
+pub fn seed_of(seed: PtyNum) -> u64 {
+    seed.0.to_bits()
+}
+
+impl PettyObjectType for PtyRng {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "seed" => SEED.clone(),
+            "random" => RANDOM.clone(),
+            "randint" => RANDINT.clone(),
+            "uniform" => UNIFORM.clone(),
+            "choice" => CHOICE.clone(),
+            "shuffle" => SHUFFLE.clone(),
+            "sample" => SAMPLE.clone(),
+            "gauss" => GAUSS.clone(),
+            "__repr__" => __REPR__.clone(),
+            _ => panic!("rng has no attribute {key}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("rng is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for PtyRng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rng at {:?}", self as *const Self)
+    }
+}
+
+/// `Rng()` is seeded from the clock, `Rng(seed)` always yields the same sequence.
+#[pettymethod]
+pub fn __init__(args: FuncArgs) -> PtyRng {
+    let rng = match args.0.first() {
+        Some(seed) => Rng::seeded(seed_of(seed.downcast().expect("Expected Number"))),
+        None => Rng::from_entropy(),
+    };
+    PtyRng::new(rng)
+}
+
+#[pettymethod]
+fn __repr__(this: &PtyRng) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+#[pettymethod]
+pub fn seed(this: &PtyRng, value: PtyNum) {
+    *this.0.lock().unwrap() = Rng::seeded(seed_of(value));
+}
+
+#[pettymethod]
+pub fn random(this: &PtyRng) -> PtyNum {
+    PtyNum(this.0.lock().unwrap().random())
+}
+
+#[pettymethod]
+pub fn randint(this: &PtyRng, low: PtyNum, high: PtyNum) -> PtyNum {
+    PtyNum(this.0.lock().unwrap().randint(low.0, high.0))
+}
+
+#[pettymethod]
+pub fn uniform(this: &PtyRng, low: PtyNum, high: PtyNum) -> PtyNum {
+    let random = this.0.lock().unwrap().random();
+    PtyNum(low.0 + (high.0 - low.0) * random)
+}
+
+#[pettymethod]
+pub fn choice(this: &PtyRng, list: &PtyList) -> PettyObject {
+    let items = list.0.lock().unwrap();
+    this.0.lock().unwrap().choice(&items)
+}
+
+#[pettymethod]
+pub fn shuffle(this: &PtyRng, list: &PtyList) {
+    let mut items = list.0.lock().unwrap();
+    this.0.lock().unwrap().shuffle(&mut items);
+}
+
+#[pettymethod]
+pub fn sample(this: &PtyRng, list: &PtyList, k: PtyNum, vm: &mut Vm) -> PtyList {
+    let items = list.0.lock().unwrap().clone();
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let sample = this.0.lock().unwrap().sample(items, k.0.max(0.0) as usize);
+    vm.new_list(sample)
+}
+
+#[pettymethod]
+pub fn gauss(this: &PtyRng, mu: PtyNum, sigma: PtyNum) -> PtyNum {
+    PtyNum(this.0.lock().unwrap().gauss(mu.0, sigma.0))
+}
@@ -0,0 +1,180 @@
+use regex::{Captures, Match};
+
+use crate::vm::prelude::*;
+
+/// A single match, with `start` and `end` counted in characters.
+#[derive(Clone)]
+pub struct PtyMatch {
+    text: Arc<str>,
+    start: usize,
+    end: usize,
+}
+
+impl PtyMatch {
+    pub fn new(haystack: &str, found: Match) -> Self {
+        let start = haystack[..found.start()].chars().count();
+        Self {
+            text: found.as_str().into(),
+            start,
+            end: start + found.as_str().chars().count(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PtyCaptures {
+    groups: Vec<Option<PtyMatch>>,
+    names: Arc<[Option<Arc<str>>]>,
+}
+
+impl PtyCaptures {
+    pub fn new(haystack: &str, captures: &Captures, names: Arc<[Option<Arc<str>>]>) -> Self {
+        let groups = captures.iter();
+        let groups = groups.map(|group| group.map(|group| PtyMatch::new(haystack, group)));
+        Self {
+            groups: groups.collect(),
+            names,
+        }
+    }
+    fn group(&self, key: &PettyObject) -> Option<&PtyMatch> {
+        let index = if let Some(PtyNum(index)) = key.downcast_ref() {
+            #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+            let index = index.max(0.0) as usize;
+            index
+        } else if let Some(PtyStr(name)) = key.downcast_ref() {
+            let position = self.names.iter().position(|n| n.as_deref() == Some(name));
+            position.unwrap_or_else(|| panic!("No group named {name}"))
+        } else {
+            panic!("Expected Number or Str, got {key}")
+        };
+        self.groups.get(index)?.as_ref()
+    }
+}
+
+impl PettyObjectType for PtyMatch {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "text" | "as_str" => PtyStr(self.text.clone()).into(),
+            #[allow(clippy::cast_precision_loss)]
+            "start" => PtyNum(self.start as f64).into(),
+            #[allow(clippy::cast_precision_loss)]
+            "end" => PtyNum(self.end as f64).into(),
+            "__repr__" => MATCH_REPR.clone(),
+            "__is_eq__" => MATCH_IS_EQ.clone(),
+            _ => panic!("match has no attribute {key}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("match is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl PettyObjectType for PtyCaptures {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "get" => GET.clone(),
+            "__get_index__" => __GET_INDEX__.clone(),
+            "groups" => GROUPS.clone(),
+            "named" => NAMED.clone(),
+            "len" => LEN.clone(),
+            "__repr__" => CAPTURES_REPR.clone(),
+            _ => panic!("captures has no attribute {key}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("captures is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for PtyMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Match({}, {}..{})", self.text, self.start, self.end)
+    }
+}
+
+impl fmt::Display for PtyCaptures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Captures(")?;
+        for (index, group) in self.groups.iter().enumerate() {
+            let seperator = if index == 0 { "" } else { ", " };
+            match group {
+                Some(group) => write!(f, "{seperator}{}", group.text)?,
+                None => write!(f, "{seperator}None")?,
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+#[pettymethod]
+fn match_repr(this: &PtyMatch) -> PtyStr {
+    PtyStr(this.text.clone())
+}
+
+#[pettymethod]
+fn match_is_eq(lhs: &PtyMatch, rhs: &PettyObject) -> PettyObject {
+    let is_eq = if let Some(rhs) = rhs.downcast_ref::<PtyMatch>() {
+        (&lhs.text, lhs.start, lhs.end) == (&rhs.text, rhs.start, rhs.end)
+    } else if let Some(PtyStr(rhs)) = rhs.downcast_ref() {
+        lhs.text == *rhs
+    } else {
+        false
+    };
+    PtyBool::new(is_eq)
+}
+
+#[pettymethod]
+fn captures_repr(this: &PtyCaptures) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+/// The text of a group by index or name, `None` if the group didn't participate.
+#[pettymethod]
+fn get(this: &PtyCaptures, key: &PettyObject) -> Option<PettyObject> {
+    this.group(key)
+        .map(|group| PtyStr(group.text.clone()).into())
+}
+
+#[pettymethod]
+fn __get_index__(this: &PtyCaptures, key: &PettyObject) -> PettyObject {
+    let group = this
+        .group(key)
+        .unwrap_or_else(|| panic!("Group {key} did not match"));
+    PtyStr(group.text.clone()).into()
+}
+
+#[pettymethod]
+fn groups(this: &PtyCaptures, vm: &mut Vm) -> PtyList {
+    let groups = this.groups.iter().skip(1);
+    let groups = groups.map(|group| {
+        let group = group
+            .as_ref()
+            .map(|group| PtyStr(group.text.clone()).into());
+        PtyOption::new(group)
+    });
+    vm.new_list(groups.collect())
+}
+
+#[pettymethod]
+fn named(this: &PtyCaptures, vm: &mut Vm) -> PtyDict {
+    let mut map = DictMap::new();
+    for (name, group) in this.names.iter().zip(&this.groups) {
+        let (Some(name), Some(group)) = (name, group) else {
+            continue;
+        };
+        map.insert(name.clone(), PtyStr(group.text.clone()).into());
+    }
+    vm.new_dict(map)
+}
+
+#[pettymethod]
+fn len(this: &PtyCaptures) -> PtyNum {
+    #[allow(clippy::cast_precision_loss)]
+    PtyNum(this.groups.len() as f64)
+}
@@ -0,0 +1,86 @@
+mod captures;
+mod regex;
+
+use crate::vm::prelude::*;
+
+use self::regex::PtyRegex;
+
+pub fn init() -> Module {
+    let dict = Dict::from([
+        ("__repr__".into(), __REPR__.clone()),
+        ("compile".into(), COMPILE.clone()),
+        ("escape".into(), ESCAPE.clone()),
+        ("is_match".into(), IS_MATCH.clone()),
+        ("find".into(), FIND.clone()),
+        ("find_all".into(), FIND_ALL.clone()),
+        ("captures".into(), CAPTURES.clone()),
+        ("replace".into(), REPLACE.clone()),
+        ("replace_all".into(), REPLACE_ALL.clone()),
+        ("split".into(), SPLIT.clone()),
+    ]);
+
+    Module {
+        name: "re".into(),
+        dict: Mutex::new(dict).into(),
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &Module) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+#[pettymethod]
+fn compile(pattern: &PtyStr) -> PtyRegex {
+    PtyRegex::cached(&pattern.0)
+}
+
+#[pettymethod]
+fn escape(text: &PtyStr) -> PtyStr {
+    PtyStr(::regex::escape(&text.0).into())
+}
+
+/// Compiles the pattern passed as the first argument and forwards the rest to `method`.
+fn call_compiled(vm: &mut Vm, method: &PettyObject, args: FuncArgs) -> PettyObject {
+    let (pattern, args) = args.0.split_first().expect("Too Few Arguments");
+    let pattern = pattern.downcast_ref::<PtyStr>().expect("Expected Str");
+    let regex: PettyObject = PtyRegex::cached(&pattern.0).into();
+    let mut items = vec![&regex];
+    items.extend_from_slice(args);
+    method.call(vm, method, FuncArgs(&items))
+}
+
+#[pettymethod]
+fn is_match(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_compiled(vm, &regex::IS_MATCH, args)
+}
+
+#[pettymethod]
+fn find(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_compiled(vm, &regex::FIND, args)
+}
+
+#[pettymethod]
+fn find_all(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_compiled(vm, &regex::FIND_ALL, args)
+}
+
+#[pettymethod]
+fn captures(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_compiled(vm, &regex::CAPTURES, args)
+}
+
+#[pettymethod]
+fn replace(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_compiled(vm, &regex::REPLACE, args)
+}
+
+#[pettymethod]
+fn replace_all(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_compiled(vm, &regex::REPLACE_ALL, args)
+}
+
+#[pettymethod]
+fn split(vm: &mut Vm, args: FuncArgs) -> PettyObject {
+    call_compiled(vm, &regex::SPLIT, args)
+}
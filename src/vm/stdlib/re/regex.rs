@@ -0,0 +1,146 @@
+use hashbrown::HashMap;
+use regex::{Captures, Regex};
+
+use crate::vm::prelude::*;
+
+use super::captures::{PtyCaptures, PtyMatch};
+
+/// Compiled patterns shared by every `re` call, cleared once it grows past `CACHE_CAPACITY`.
+static CACHE: Lazy<Mutex<HashMap<Arc<str>, Regex>>> = Lazy::new(Mutex::default);
+const CACHE_CAPACITY: usize = 256;
+
+#[derive(Clone)]
+pub struct PtyRegex(pub Regex);
+
+impl PtyRegex {
+    /// # Panics
+    /// If `pattern` is not a valid regular expression.
+    pub fn cached(pattern: &str) -> Self {
+        let mut cache = CACHE.lock().unwrap();
+        if let Some(regex) = cache.get(pattern) {
+            return Self(regex.clone());
+        }
+        let regex = Regex::new(pattern).unwrap_or_else(|err| panic!("{err}"));
+        if cache.len() >= CACHE_CAPACITY {
+            cache.clear();
+        }
+        cache.insert(pattern.into(), regex.clone());
+        Self(regex)
+    }
+    /// Replaces matches with either a replacement string, which may refer to groups
+    /// as `$1` or `$name`, or with the result of calling a function on the captures.
+    fn replace(
+        &self,
+        vm: &mut Vm,
+        text: &PtyStr,
+        replacement: &PettyObject,
+        limit: usize,
+    ) -> PtyStr {
+        if let Some(PtyStr(replacement)) = replacement.downcast_ref() {
+            return PtyStr(self.0.replacen(&text.0, limit, &**replacement).into());
+        }
+        let names = self.names();
+        let replaced = self.0.replacen(&text.0, limit, |captures: &Captures| {
+            let captures: PettyObject = PtyCaptures::new(&text.0, captures, names.clone()).into();
+            let output = replacement.call(vm, replacement, FuncArgs(&[&captures]));
+            output.force_repr(vm).0.to_string()
+        });
+        PtyStr(replaced.into())
+    }
+    fn names(&self) -> Arc<[Option<Arc<str>>]> {
+        self.0
+            .capture_names()
+            .map(|name| name.map(Arc::from))
+            .collect()
+    }
+}
+
+impl PettyObjectType for PtyRegex {
+    fn get_item(&self, _vm: &mut Vm, _this: &PettyObject, key: &str) -> PettyObject {
+        match key {
+            "is_match" => IS_MATCH.clone(),
+            "find" => FIND.clone(),
+            "find_all" => FIND_ALL.clone(),
+            "captures" => CAPTURES.clone(),
+            "captures_all" => CAPTURES_ALL.clone(),
+            "replace" => REPLACE.clone(),
+            "replace_all" => REPLACE_ALL.clone(),
+            "split" => SPLIT.clone(),
+            "pattern" => PtyStr(self.0.as_str().into()).into(),
+            "__repr__" => __REPR__.clone(),
+            _ => panic!("regex has no attribute {key}"),
+        }
+    }
+    fn call(&self, _vm: &mut Vm, _this: &PettyObject, _args: FuncArgs) -> PettyObject {
+        panic!("regex is not callable")
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl fmt::Display for PtyRegex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Regex({})", self.0.as_str())
+    }
+}
+
+#[pettymethod]
+fn __repr__(this: &PtyRegex) -> PtyStr {
+    PtyStr::from(this.to_string())
+}
+
+#[pettymethod]
+pub fn is_match(this: &PtyRegex, text: &PtyStr) -> PettyObject {
+    PtyBool::new(this.0.is_match(&text.0))
+}
+
+#[pettymethod]
+pub fn find(this: &PtyRegex, text: &PtyStr) -> Option<PettyObject> {
+    let found = this.0.find(&text.0)?;
+    Some(PtyMatch::new(&text.0, found).into())
+}
+
+#[pettymethod]
+pub fn find_all(this: &PtyRegex, text: &PtyStr, vm: &mut Vm) -> PtyList {
+    let matches = this.0.find_iter(&text.0);
+    let matches = matches.map(|found| PtyMatch::new(&text.0, found).into());
+    vm.new_list(matches.collect())
+}
+
+#[pettymethod]
+pub fn captures(this: &PtyRegex, text: &PtyStr) -> Option<PettyObject> {
+    let captures = this.0.captures(&text.0)?;
+    Some(PtyCaptures::new(&text.0, &captures, this.names()).into())
+}
+
+#[pettymethod]
+pub fn captures_all(this: &PtyRegex, text: &PtyStr, vm: &mut Vm) -> PtyList {
+    let names = this.names();
+    let captures = this.0.captures_iter(&text.0);
+    let captures =
+        captures.map(|captures| PtyCaptures::new(&text.0, &captures, names.clone()).into());
+    vm.new_list(captures.collect())
+}
+
+#[pettymethod]
+pub fn replace(this: &PtyRegex, text: &PtyStr, replacement: &PettyObject, vm: &mut Vm) -> PtyStr {
+    this.replace(vm, text, replacement, 1)
+}
+
+#[pettymethod]
+pub fn replace_all(
+    this: &PtyRegex,
+    text: &PtyStr,
+    replacement: &PettyObject,
+    vm: &mut Vm,
+) -> PtyStr {
+    this.replace(vm, text, replacement, 0)
+}
+
+#[pettymethod]
+pub fn split(this: &PtyRegex, text: &PtyStr, vm: &mut Vm) -> PtyList {
+    let parts = this.0.split(&text.0);
+    let parts = parts.map(|part| vm.new_str(part)).collect();
+    vm.new_list(parts)
+}
@@ -1,5 +1,5 @@
 use std::{
-    fmt,
+    fmt, panic,
     sync::{Arc, Mutex},
     thread::{JoinHandle, ThreadId},
 };
@@ -22,9 +22,10 @@ pub struct ThreadHandle {
 impl ThreadHandle {
     #[inline]
     pub fn spawn(vm: &mut Vm, func: &PettyObject) -> Self {
+        let limits = vm.inner.limits;
         let mut vm = vm.spawn_new();
         let func = func.clone();
-        let join_handle = std::thread::spawn(move || func.call(&mut vm, &func, FuncArgs(&[])));
+        let join_handle = limits.spawn(move || func.call(&mut vm, &func, FuncArgs(&[])));
         let id = join_handle.thread().id();
         Self {
             handle: Mutex::new(Some(join_handle)).into(),
@@ -52,7 +53,9 @@ impl PettyObjectType for ThreadHandle {
 #[pettymethod]
 pub fn join(this: ThreadHandle) -> PettyObject {
     let handle = this.handle.lock().unwrap().take().unwrap();
-    handle.join().unwrap()
+    handle
+        .join()
+        .unwrap_or_else(|payload| panic::resume_unwind(payload))
 }
 
 #[pettymethod]
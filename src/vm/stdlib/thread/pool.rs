@@ -1,4 +1,4 @@
-use std::thread::JoinHandle;
+use std::{panic, thread::JoinHandle};
 
 use crate::vm::prelude::*;
 
@@ -40,21 +40,24 @@ fn __repr__(this: &ThreadPool) -> PtyStr {
 
 #[pettymethod]
 fn spawn(this: &ThreadPool, func: &PettyObject, vm: &mut Vm) {
+    let limits = vm.inner.limits;
     let mut vm = vm.spawn_new();
     let func = func.clone();
-    let join_handle = std::thread::spawn(move || func.call(&mut vm, &func, FuncArgs(&[])));
+    let join_handle = limits.spawn(move || func.call(&mut vm, &func, FuncArgs(&[])));
     this.0.lock().unwrap().push(join_handle);
 }
 
 #[pettymethod]
-fn join(this: &ThreadPool) -> PtyList {
+fn join(this: &ThreadPool, vm: &mut Vm) -> PtyList {
     let mut lock = this.0.lock().unwrap();
     let threads: Vec<_> = std::mem::take(lock.as_mut());
     drop(lock);
     let mut output = vec![];
     for thread in threads {
-        let object = thread.join().unwrap();
+        let object = thread
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload));
         output.push(object);
     }
-    PtyList(Mutex::new(output).into())
+    vm.new_list(output)
 }
@@ -0,0 +1,194 @@
+use crate::{
+    optimizer::optimize,
+    parser::parse,
+    vm::{self, bytecode::Compiler, Engine},
+};
+
+/// Runs `source` on both engines, with and without optimizing it,
+/// comparing the value of every top level statement.
+fn assert_same(source: &str) {
+    let ast = parse(source).unwrap();
+    let optimized = optimize(&ast);
+    let display = |ast, engine| {
+        vm::run_with_engine(ast, engine)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    };
+    let expected = display(&ast, Engine::TreeWalk);
+    assert_eq!(expected, display(&ast, Engine::Bytecode), "{source}");
+    assert_eq!(expected, display(&optimized, Engine::TreeWalk), "{source}");
+    assert_eq!(expected, display(&optimized, Engine::Bytecode), "{source}");
+}
+
+#[test]
+fn expressions() {
+    assert_same("1 + 2 * 3; -(4 - 6); !true; 7 % 4 == 3; \"a\" + 'b'; [1, [2, 3]];");
+    assert_same("x = 5; x * x; [x, x + 1].map(|y| { return y * 2; });");
+    assert_same("d = dict(); d.set(\"k\", 1); d[\"k\"]; d.get(\"missing\");");
+}
+
+#[test]
+fn control_flow() {
+    assert_same(
+        "total = 0;
+        for i in range(10) { if i % 2 == 0 { total = total + i; } elif i == 5 { total = total - 1; } else { } }
+        total;
+        n = 0;
+        while n < 5 { n = n + 1; }
+        n;",
+    );
+}
+
+#[test]
+fn functions_and_closures() {
+    assert_same(
+        "fn fact(n) { if n <= 1 { return 1; } return n * fact(n - 1); }
+        fact(10);
+        fn adder(x) { return |y| { return x + y; }; }
+        add_two = adder(2);
+        add_two(5);
+        fn first(list) { return list.get(0)?; }
+        first([]);
+        first([3]);",
+    );
+}
+
+#[test]
+fn scoping() {
+    assert_same(
+        "count = 1;
+        fn shadow() { count = count + 1; return count; }
+        shadow();
+        count;
+        fn outer(a) { fn middle() { return || { return a + count; }; } inner = middle(); return inner(); }
+        outer(10);
+        fn late() { f = || { return value; }; value = 1; return f; }
+        value = 5;
+        read_value = late();
+        read_value();",
+    );
+}
+
+#[test]
+fn undefined_names() {
+    let ast =
+        parse("fn f() { return missing + 1; } x = 1; g = |y| { return x + y + other; };").unwrap();
+    let errors: Vec<_> = Compiler::compile(["print".into()], std::slice::from_ref(&ast))
+        .err()
+        .unwrap()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        errors,
+        ["Undefined name `missing`", "Undefined name `other`"]
+    );
+}
+
+#[test]
+fn nonlocal_without_binding() {
+    let ast = parse("x = 1; fn f() { nonlocal x; x = 2; }").unwrap();
+    let errors = Compiler::compile([], std::slice::from_ref(&ast))
+        .err()
+        .unwrap();
+    assert_eq!(
+        errors[0].to_string(),
+        "No binding for nonlocal `x` in an enclosing function"
+    );
+}
+
+#[test]
+fn constant_expressions() {
+    assert_same(
+        "day = 60 * 60 * 24; day; 7 / 2 % 3; 0.1 + 0.2 == 0.3; 'ab' + 'cd' >= 'abc';
+        fn pick() { if 2 > 1 { return \"yes\"; } else { return \"no\"; } }
+        pick();
+        fn dead() { while 1 > 2 { return 1; } { { return 2; } } }
+        dead();
+        if !true { 1; } else { 2; }",
+    );
+}
+
+#[test]
+fn polymorphic_call_sites() {
+    assert_same(
+        "fn len_of(x) { return x.len(); }
+        [[1, 2], \"abc\", range(4)].map(len_of);
+        fn add(a, b) { return a + b; }
+        [add(1, 2), add(\"a\", \"b\"), add([1], [2]), add(0.5, 2)];
+        fn truthy(x) { if x { return 1; } return 0; }
+        [truthy(true), truthy(0), truthy([1]), truthy(null)];",
+    );
+}
+
+#[test]
+fn classes() {
+    assert_same(
+        "class Point(x, y) {
+            fn sum(self) { return self.x + self.y; }
+        }
+        point = Point(3, 4);
+        point.x;
+        point.sum();",
+    );
+}
+
+#[test]
+fn generators() {
+    assert_same(
+        "fn evens() { i = 0; while true { yield i; i = i + 2; } }
+        evens().take(4).collect();
+        fn echo() { while true { value = yield null; yield value; } }
+        gen = echo();
+        gen.next();
+        gen.send(5);",
+    );
+}
+
+#[test]
+fn threads() {
+    assert_same(
+        "handle = std.thread.spawn(|| { return [1, 2, 3].sum(); });
+        handle.join();",
+    );
+}
+
+#[test]
+fn deep_recursion() {
+    assert_same(
+        "fn count(n) { if n == 0 { return 0; } return count(n - 1) + 1; }
+        count(9000);
+        fn evens(n) { if n == 0 { return []; } rest = evens(n - 1); return rest + [n * 2]; }
+        evens(2000).len();",
+    );
+}
+
+#[test]
+fn tail_calls() {
+    assert_same(
+        "fn loop(n, total) { if n == 0 { return total; } return loop(n - 1, total + n); }
+        loop(20000, 0);
+        fn countdown(n) { if n > 0 { return countdown(n - 1); } return n; }
+        countdown(20000);
+        fn outer(n) { fn inner(n) { if n == 0 { return 'done'; } return inner(n - 1); } return inner(n); }
+        outer(20000);
+        fn gen(n) { yield n; return gen(n + 1); }
+        g = gen(0);
+        [g.next(), g.next()];",
+    );
+}
+
+#[test]
+#[should_panic(expected = "Expected 2 arguments, got 1.")]
+fn wrong_argument_count() {
+    let ast = parse("fn add(a, b) { return a + b; } add(1);").unwrap();
+    vm::run_with_engine(&ast, Engine::Bytecode);
+}
+
+#[test]
+fn function_repr() {
+    let ast = parse("fn named() { return 1; } named.__repr__();").unwrap();
+    let values = vm::run_with_engine(&ast, Engine::Bytecode);
+    assert!(values[1].to_string().starts_with("function named at "));
+}
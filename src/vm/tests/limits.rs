@@ -0,0 +1,142 @@
+use std::{thread, time::Duration};
+
+use crate::{
+    error::RuntimeError,
+    parser::parse,
+    vm::{self, CancelHandle, Engine, Limits},
+};
+
+const ENGINES: [Engine; 2] = [Engine::TreeWalk, Engine::Bytecode];
+
+/// Runs `source` on both engines, expecting each run to be aborted by `error`.
+fn assert_fails(source: &str, limits: Limits, error: &RuntimeError) {
+    let ast = parse(source).unwrap();
+    for engine in ENGINES {
+        let result = vm::run_with_limits(&ast, engine, limits).map(|_| ());
+        assert_eq!(result.as_ref(), Err(error), "{source}");
+    }
+}
+
+#[test]
+fn recursion_limit() {
+    let limits = Limits {
+        recursion: 50,
+        ..Limits::default()
+    };
+    let error = RuntimeError::Recursion { limit: 50 };
+    for source in [
+        "fn f(n) { return f(n + 1) + 1; } f(0);",
+        "fn g() { yield g().next(); } g().next();",
+        "fn h() { return [h()]; } std.thread.spawn(h).join();",
+    ] {
+        assert_fails(source, limits, &error);
+    }
+    let ast = parse("fn f(n) { if n == 0 { return 0; } return f(n - 1) + 1; } f(49);").unwrap();
+    for engine in ENGINES {
+        assert!(vm::run_with_limits(&ast, engine, limits).is_ok());
+    }
+}
+
+#[test]
+fn thread_recursion_limit() {
+    let limits = Limits {
+        thread_recursion: 20,
+        ..Limits::default()
+    };
+    let error = RuntimeError::Recursion { limit: 20 };
+    for source in [
+        "fn f(n) { return f(n + 1) + 1; } std.thread.spawn(|| { return f(0); }).join();",
+        "fn f(n) { return f(n + 1) + 1; } fn g() { yield f(0); } g().next();",
+    ] {
+        assert_fails(source, limits, &error);
+    }
+    // Generators can nest `thread_recursion` calls past the depth they were created at.
+    let error = RuntimeError::Recursion { limit: 31 };
+    let source = "fn f(n) { if n == 10 { return g().next(); } return [f(n + 1)]; }
+        fn g() { yield h(0); } fn h(n) { return h(n + 1) + 1; } f(0);";
+    assert_fails(source, limits, &error);
+}
+
+#[test]
+fn step_limit() {
+    let limits = Limits {
+        steps: Some(10_000),
+        ..Limits::default()
+    };
+    let error = RuntimeError::Steps { limit: 10_000 };
+    assert_fails("i = 0; while true { i = i + 1; }", limits, &error);
+    assert_fails(
+        "std.thread.spawn(|| { while true { } }).join();",
+        limits,
+        &error,
+    );
+    let ast = parse("total = 0; for i in range(100) { total = total + i; } total;").unwrap();
+    for engine in ENGINES {
+        assert!(vm::run_with_limits(&ast, engine, limits).is_ok());
+    }
+}
+
+#[test]
+fn timeout() {
+    let limit = Duration::from_millis(50);
+    let limits = Limits {
+        timeout: Some(limit),
+        ..Limits::default()
+    };
+    let error = RuntimeError::Timeout { limit };
+    assert_fails("while true { }", limits, &error);
+    assert_fails(
+        "pool = std.thread.ThreadPool(); pool.spawn(|| { while true { } }); pool.join();",
+        limits,
+        &error,
+    );
+}
+
+#[test]
+fn cancel() {
+    let ast = parse("fn spin() { while true { } } spin();").unwrap();
+    for engine in ENGINES {
+        let cancel = CancelHandle::new();
+        let trigger = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            trigger.cancel();
+        });
+        let result = vm::run_cancellable(&ast, engine, Limits::default(), &cancel);
+        assert_eq!(result.map(|_| ()), Err(RuntimeError::Cancelled));
+    }
+}
+
+#[test]
+fn memory_limit() {
+    let limits = Limits {
+        memory: Some(1 << 20),
+        ..Limits::default()
+    };
+    let error = RuntimeError::Memory { limit: 1 << 20 };
+    for source in [
+        "\"x\" * 1e12;",
+        "items = []; while true { items.push(1); }",
+        "items = [1]; while true { items = items + items; }",
+        "items = []; while true { items = [items]; }",
+        "text = \"ab\"; while true { text = text + text; }",
+        "range(1e12).collect();",
+        "\"{:1000000000}\".format(1);",
+    ] {
+        assert_fails(source, limits, &error);
+    }
+    // Memory is given back as strings are dropped and containers shrink,
+    // so these only ever hold a little at once.
+    for source in [
+        "for i in range(100) { text = \"x\" * 100000; }",
+        "items = []; for i in range(200000) { items.push(1); items.pop(); }",
+        "items = []; for i in range(200000) { items.insert(0, 1); items.remove(0); }",
+        "items = []; for i in range(20000) { items.extend(range(10)); items.clear(); }",
+        "entries = dict(); for i in range(200000) { entries.set(\"k\", i); entries.remove(\"k\"); }",
+    ] {
+        let ast = parse(source).unwrap();
+        for engine in ENGINES {
+            assert!(vm::run_with_limits(&ast, engine, limits).is_ok(), "{source}");
+        }
+    }
+}
@@ -1,2 +1,8 @@
 #[cfg(test)]
+mod differential;
+#[cfg(test)]
+mod limits;
+#[cfg(test)]
+mod os;
+#[cfg(test)]
 mod pty_tests;
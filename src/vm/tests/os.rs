@@ -0,0 +1,22 @@
+use crate::{
+    parser::parse,
+    vm::{self, Engine},
+};
+
+#[test]
+fn args() {
+    vm::set_args(vec!["script.pty".into(), "--flag".into(), "ünïcode".into()]);
+    let ast = parse(
+        r#"
+        args = std.os.args;
+        std.test.assert_eq(args.len(), 3);
+        std.test.assert_eq(args.get(0).unwrap(), "script.pty");
+        std.test.assert_eq(args.get(1).unwrap(), "--flag");
+        std.test.assert_eq(args.get(2).unwrap(), "ünïcode");
+        "#,
+    )
+    .unwrap();
+    for engine in [Engine::TreeWalk, Engine::Bytecode] {
+        vm::run_with_engine(&ast, engine);
+    }
+}
@@ -1,16 +1,38 @@
 use std::fs;
 
-use crate::{parser::parse, vm};
+use crate::{
+    optimizer::optimize,
+    parser::parse,
+    vm::{self, Engine},
+};
 
 #[test]
 fn run_pty_tests() {
+    run_pty_tests_with(Engine::TreeWalk, false);
+}
+
+#[test]
+fn run_pty_tests_bytecode() {
+    run_pty_tests_with(Engine::Bytecode, false);
+}
+
+#[test]
+fn run_pty_tests_optimized() {
+    run_pty_tests_with(Engine::TreeWalk, true);
+    run_pty_tests_with(Engine::Bytecode, true);
+}
+
+fn run_pty_tests_with(engine: Engine, optimized: bool) {
     let files = fs::read_dir("pty_tests")
         .unwrap()
         .collect::<Result<Vec<_>, _>>()
         .unwrap();
     for file in files {
         let content = fs::read_to_string(file.path()).unwrap();
-        let ast = parse(&content).unwrap();
-        vm::run_virtual_machine(&ast);
+        let mut ast = parse(&content).unwrap();
+        if optimized {
+            ast = optimize(&ast);
+        }
+        vm::run_with_engine(&ast, engine);
     }
 }